@@ -0,0 +1,69 @@
+//! Compares eager parsing against `Message::parse_header_only` for a router that only inspects
+//! the command of each message, and `Message::try_from` against `parse_into` with reused buffers
+//! for a long message. Run with `cargo bench --bench parse`.
+
+use pircolate::message::{parse_into, ParseBuffers};
+use pircolate::Message;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 200_000;
+
+const LINES: [&str; 4] = [
+    "@badge-info=;badges=broadcaster/1;color=#FF0000;display-name=Memelord;emotes=;id=1 :memelord!memelord@memelord.tmi.twitch.tv PRIVMSG #memes :memes are great and everyone should know it",
+    ":irc.example.com 005 robot AWAYLEN=200 CASEMAPPING=rfc1459 CHANMODES=beI,k,l,imnpst CHANTYPES=# NETWORK=Memes PREFIX=(ov)@+ :are supported by this server",
+    ":irc.example.com 353 robot = #memes :@overlord +robot memelord alice bob carol dave eve mallory",
+    "PING :irc.example.com",
+];
+
+const LONG_MESSAGE: &str = "@badge-info=subscriber/12;badges=subscriber/12,premium/1;color=#00FF7F;display-name=Memelord;emotes=25:0-4,12-16/1902:6-10;first-msg=0;flags=;id=b34ccfc7-4977-403a-8a94-33c6bac34fb8;mod=0;room-id=1337;subscriber=1;tmi-sent-ts=1507246572675;turbo=0;user-id=1337;user-type= :memelord!memelord@memelord.tmi.twitch.tv PRIVMSG #memes :Kappa Keepo Kappa memes are great and everyone should know it, so here is a rather long message full of words to parse";
+
+fn bench(name: &str, lines: &[&str], mut f: impl FnMut(&str) -> usize) {
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        for line in lines.iter() {
+            black_box(f(black_box(line)));
+        }
+    }
+
+    let elapsed = start.elapsed();
+    let per_message = elapsed / (ITERATIONS * lines.len() as u32);
+    println!(
+        "{:<24} {:>8?} per message ({:?} total)",
+        name, per_message, elapsed
+    );
+}
+
+fn main() {
+    // NOTE: A short warm up keeps the first benchmark from paying for cold caches.
+    let warm_up = Instant::now();
+    while warm_up.elapsed() < Duration::from_millis(200) {
+        for line in LINES.iter() {
+            black_box(
+                Message::try_from(*line)
+                    .map(|msg| msg.raw_command().len())
+                    .ok(),
+            );
+        }
+    }
+
+    bench("eager command-only", &LINES, |line| {
+        Message::try_from(line).map_or(0, |msg| msg.raw_command().len())
+    });
+    bench("header-only command", &LINES, |line| {
+        Message::parse_header_only(line).map_or(0, |msg| msg.raw_command().len())
+    });
+    bench("header-only with args", &LINES, |line| {
+        Message::parse_header_only(line).map_or(0, |msg| msg.raw_args().count())
+    });
+
+    let mut buffers = ParseBuffers::new();
+    bench("long message", &[LONG_MESSAGE], |line| {
+        Message::try_from(line).map_or(0, |msg| msg.raw_args().count())
+    });
+    bench("long message into", &[LONG_MESSAGE], |line| {
+        parse_into(line, &mut buffers).map_or(0, |msg| msg.raw_args().count())
+    });
+}
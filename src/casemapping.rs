@@ -0,0 +1,84 @@
+//! The casemapping module contains the casemappings servers use when comparing
+//! nicknames and channel names case-insensitively.
+
+/// A casemapping as advertised by the CASEMAPPING token of RPL_ISUPPORT (005).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CaseMapping {
+    /// Only the ASCII letters `A-Z` are mapped to `a-z`.
+    Ascii,
+    /// The ASCII letters are mapped as well as `[]\~` to `{}|^`.
+    #[default]
+    Rfc1459,
+    /// The ASCII letters are mapped as well as `[]\` to `{}|`.
+    StrictRfc1459,
+}
+
+impl CaseMapping {
+    /// Parses the value of a CASEMAPPING token. If the casemapping is unknown, it returns `None`.
+    pub fn parse(value: &str) -> Option<CaseMapping> {
+        match value {
+            "ascii" => Some(CaseMapping::Ascii),
+            "rfc1459" => Some(CaseMapping::Rfc1459),
+            "strict-rfc1459" => Some(CaseMapping::StrictRfc1459),
+            _ => None,
+        }
+    }
+
+    /// Maps a single character to its lowercase form under this casemapping.
+    pub fn to_lowercase_char(self, c: char) -> char {
+        match (self, c) {
+            (_, 'A'..='Z') => c.to_ascii_lowercase(),
+            (CaseMapping::Rfc1459, '[') | (CaseMapping::StrictRfc1459, '[') => '{',
+            (CaseMapping::Rfc1459, ']') | (CaseMapping::StrictRfc1459, ']') => '}',
+            (CaseMapping::Rfc1459, '\\') | (CaseMapping::StrictRfc1459, '\\') => '|',
+            (CaseMapping::Rfc1459, '~') => '^',
+            _ => c,
+        }
+    }
+
+    /// Maps a string to its lowercase form under this casemapping.
+    pub fn to_lowercase(self, value: &str) -> String {
+        value.chars().map(|c| self.to_lowercase_char(c)).collect()
+    }
+
+    /// Compares two strings for equality under this casemapping.
+    pub fn eq_ignore_case(self, left: &str, right: &str) -> bool {
+        left.len() == right.len()
+            && left
+                .chars()
+                .map(|c| self.to_lowercase_char(c))
+                .eq(right.chars().map(|c| self.to_lowercase_char(c)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_casemapping() {
+        assert_eq!(Some(CaseMapping::Ascii), CaseMapping::parse("ascii"));
+        assert_eq!(Some(CaseMapping::Rfc1459), CaseMapping::parse("rfc1459"));
+        assert_eq!(
+            Some(CaseMapping::StrictRfc1459),
+            CaseMapping::parse("strict-rfc1459")
+        );
+        assert_eq!(None, CaseMapping::parse("rfc7613"));
+    }
+
+    #[test]
+    fn test_to_lowercase() {
+        assert_eq!("robot{}|^", CaseMapping::Rfc1459.to_lowercase("ROBOT[]\\~"));
+        assert_eq!(
+            "robot{}|~",
+            CaseMapping::StrictRfc1459.to_lowercase("ROBOT[]\\~")
+        );
+        assert_eq!("robot[]\\~", CaseMapping::Ascii.to_lowercase("ROBOT[]\\~"));
+    }
+
+    #[test]
+    fn test_eq_ignore_case() {
+        assert!(CaseMapping::Rfc1459.eq_ignore_case("Robot[1]", "robot{1}"));
+        assert!(!CaseMapping::Ascii.eq_ignore_case("Robot[1]", "robot{1}"));
+    }
+}
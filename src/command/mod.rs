@@ -1,11 +1,15 @@
 //! The command module contains everything needed to perform strongly typed access
 //! to commands associated with a message.
 
+mod registry;
+
 #[cfg(feature = "twitch-client")]
 mod twitch;
 #[cfg(feature = "twitch-client")]
 pub use twitch::*;
 
+pub use registry::CommandRegistry;
+
 use std::ops::Range;
 use std::slice::Iter;
 
@@ -20,6 +24,15 @@ impl<'a> ArgumentIter<'a> {
     pub(crate) fn new(source: &'a str, iter: Iter<'a, Range<usize>>) -> ArgumentIter<'a> {
         ArgumentIter { source, iter }
     }
+
+    /// Get the argument at the given index of the remaining arguments, without advancing the iterator.
+    /// If there are fewer remaining arguments than the index, it returns `None`.
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        self.iter
+            .as_slice()
+            .get(index)
+            .map(|range| &self.source[range.clone()])
+    }
 }
 
 impl<'a> Iterator for ArgumentIter<'a> {
@@ -28,6 +41,10 @@ impl<'a> Iterator for ArgumentIter<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next().map(|range| &self.source[range.clone()])
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
 
 impl<'a> DoubleEndedIterator for ArgumentIter<'a> {
@@ -38,6 +55,44 @@ impl<'a> DoubleEndedIterator for ArgumentIter<'a> {
     }
 }
 
+impl<'a> ExactSizeIterator for ArgumentIter<'a> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+/// Splits a trailing argument into space separated `key=value` pairs, such as those used by
+/// STS policies or CTCP extensions. Tokens without an `=` yield a key with no value.
+pub fn parse_kv_trailing(trailing: &str) -> impl Iterator<Item = (&str, Option<&str>)> {
+    trailing
+        .split_whitespace()
+        .map(|token| match token.split_once('=') {
+            Some((key, value)) => (key, Some(value)),
+            None => (token, None),
+        })
+}
+
+/// A CTCP message found within the body of a PRIVMSG or NOTICE, which is wrapped in `\x01`.
+/// The first element is the CTCP command, such as `ACTION` or `VERSION`, and the second element is its payload.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Ctcp<'a>(pub &'a str, pub Option<&'a str>);
+
+impl<'a> Ctcp<'a> {
+    /// Attempts to parse a CTCP message from the body of a PRIVMSG or NOTICE. If the body is not
+    /// wrapped in `\x01`, it returns `None`.
+    pub fn parse(body: &'a str) -> Option<Ctcp<'a>> {
+        // NOTE: Some clients omit the closing delimiter, so it's optional.
+        let body = body.strip_prefix('\x01')?;
+        let body = body.strip_suffix('\x01').unwrap_or(body);
+
+        match body.split_once(' ') {
+            Some((command, payload)) if !command.is_empty() => Some(Ctcp(command, Some(payload))),
+            None if !body.is_empty() => Some(Ctcp(body, None)),
+            _ => None,
+        }
+    }
+}
+
 /// The `Command` trait is a trait that's implemented by types wishing to provide command
 /// parsing capability for usage with the `Message::command` method.
 pub trait Command {
@@ -69,6 +124,44 @@ pub trait Command {
     }
 }
 
+/// Determines whether a command is a three digit numeric reply, such as `001`.
+pub(crate) fn is_numeric(command: &str) -> bool {
+    command.len() == 3 && command.bytes().all(|c| c.is_ascii_digit())
+}
+
+/// A trait implemented by the types of command arguments, allowing the `owned_command!` macro to
+/// convert a borrowed command into an owned counterpart.
+pub trait OwnedArgument {
+    type Owned;
+
+    /// Converts the borrowed argument into its owned representation.
+    fn to_owned_argument(&self) -> Self::Owned;
+}
+
+impl OwnedArgument for &str {
+    type Owned = String;
+
+    fn to_owned_argument(&self) -> String {
+        (*self).to_owned()
+    }
+}
+
+impl<T: OwnedArgument> OwnedArgument for Option<T> {
+    type Owned = Option<T::Owned>;
+
+    fn to_owned_argument(&self) -> Option<T::Owned> {
+        self.as_ref().map(OwnedArgument::to_owned_argument)
+    }
+}
+
+impl<T: OwnedArgument> OwnedArgument for Vec<T> {
+    type Owned = Vec<T::Owned>;
+
+    fn to_owned_argument(&self) -> Vec<T::Owned> {
+        self.iter().map(OwnedArgument::to_owned_argument).collect()
+    }
+}
+
 /// A macro for simplifying the process of matching commands.
 ///
 /// # Examples
@@ -76,7 +169,7 @@ pub trait Command {
 /// Match all PING commands.
 ///
 #[cfg_attr(
-    feature = "twitch_client",
+    feature = "twitch-client",
     doc = r##"
 ```
 # #[macro_use] extern crate pircolate;
@@ -117,7 +210,10 @@ macro_rules! command_match {
 }
 
 /// A macro for creating implementations of basic commands with up to four
-/// &str arguments.
+/// &str arguments. Prefixing the final argument with `?`, as in `Quit(?reason)`,
+/// makes it an `Option<&str>` that is `None` when the argument is absent, while
+/// prefixing it with `*`, as in `Names(channel, *rest)`, collects any remaining
+/// arguments into a `Vec<&str>`.
 ///
 /// # Examples
 ///
@@ -162,6 +258,42 @@ macro_rules! command {
         }
     };
 
+    ($(#[$meta:meta])* ($command:expr => $command_name:ident($($name:ident,)* *$rest:ident))) => {
+        $(#[$meta])*
+
+        pub struct $command_name<'a>($(pub expand_param!($name),)* pub Vec<&'a str>);
+
+        impl $crate::command::Command for $command_name<'_> {
+            const NAME: &'static str = $command;
+
+            type Output<'a> = $command_name<'a>;
+
+            fn parse<'a>(mut arguments: ArgumentIter<'a>) -> Option<$command_name<'a>> {
+                $(let $name = arguments.next()?;)*
+                let $rest = arguments.collect();
+                Some($command_name($($name,)* $rest))
+            }
+        }
+    };
+
+    ($(#[$meta:meta])* ($command:expr => $command_name:ident($($name:ident,)* ?$optional:ident))) => {
+        $(#[$meta])*
+
+        pub struct $command_name<'a>($(pub expand_param!($name),)* pub Option<&'a str>);
+
+        impl $crate::command::Command for $command_name<'_> {
+            const NAME: &'static str = $command;
+
+            type Output<'a> = $command_name<'a>;
+
+            fn parse<'a>(mut arguments: ArgumentIter<'a>) -> Option<$command_name<'a>> {
+                $(let $name = arguments.next()?;)*
+                let $optional = arguments.next();
+                Some($command_name($($name,)* $optional))
+            }
+        }
+    };
+
     ($(#[$meta:meta])* ($command:expr => $command_name:ident($($name:ident),+))) => {
         $(#[$meta])*
 
@@ -180,8 +312,158 @@ macro_rules! command {
     };
 }
 
+/// A macro for generating an owned counterpart to a borrowed command, along with a `to_owned`
+/// method on the borrowed command that performs the conversion. Each element of the command is
+/// named, followed by the types of the owned command's elements. The element types must implement
+/// `OwnedArgument`.
+///
+/// # Examples
+///
+/// An owned version of a simple command "TEST" with two &str arguments.
+///
+/// ```
+/// # #[macro_use] extern crate pircolate;
+/// #
+/// # use pircolate::message;
+/// # use pircolate::command::ArgumentIter;
+/// #
+/// command! {
+///   ("TEST" => Test(user, message))
+/// }
+///
+/// owned_command! {
+///   (Test(user, message) => OwnedTest(String, String))
+/// }
+/// #
+/// # fn main() {
+/// #   let msg = message::Message::try_from("TEST bob :hello, world!").unwrap();
+/// let owned: Option<OwnedTest> = msg.command::<Test>().map(|test| test.to_owned());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! owned_command {
+    ($(#[$meta:meta])* ($command_name:ident($($name:ident),+) => $owned_name:ident($($owned_type:ty),+))) => {
+        $(#[$meta])*
+        pub struct $owned_name($(pub $owned_type),+);
+
+        impl $command_name<'_> {
+            /// Converts this command into its owned counterpart.
+            pub fn to_owned(&self) -> $owned_name {
+                let $command_name($($name),+) = self;
+                $owned_name($($crate::command::OwnedArgument::to_owned_argument($name)),+)
+            }
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! expand_param {
     ($i:ident) => { &'a str };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Message;
+    use anyhow::{Context, Result};
+
+    command! {
+        ("QUIT" => TestQuit(?reason))
+    }
+
+    command! {
+        ("KICK" => TestKick(channel, nick, ?comment))
+    }
+
+    command! {
+        ("TEST" => TestRest(user, channel, *rest))
+    }
+
+    #[test]
+    fn test_command_macro_with_optional_argument() -> Result<()> {
+        let with_reason = Message::try_from("QUIT :Gone to lunch")?;
+        let without_reason = Message::try_from("QUIT")?;
+
+        let TestQuit(reason) = with_reason.command().context("Invalid quit command.")?;
+        assert_eq!(Some("Gone to lunch"), reason);
+
+        let TestQuit(reason) = without_reason.command().context("Invalid quit command.")?;
+        assert_eq!(None, reason);
+        Ok(())
+    }
+
+    #[test]
+    fn test_command_macro_requires_preceding_arguments() -> Result<()> {
+        let full = Message::try_from("KICK #memes troll :no trolling")?;
+        let no_comment = Message::try_from("KICK #memes troll")?;
+        let no_nick = Message::try_from("KICK #memes")?;
+
+        let TestKick(channel, nick, comment) = full.command().context("Invalid kick command.")?;
+        assert_eq!(
+            ("#memes", "troll", Some("no trolling")),
+            (channel, nick, comment)
+        );
+        assert!(matches!(
+            no_comment.command::<TestKick>(),
+            Some(TestKick("#memes", "troll", None))
+        ));
+        assert!(no_nick.command::<TestKick>().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_command_macro_with_rest_arguments() -> Result<()> {
+        let msg = Message::try_from("TEST robots #memes a b :c d")?;
+        let TestRest(user, channel, rest) = msg.command().context("Invalid test command.")?;
+
+        assert_eq!("robots", user);
+        assert_eq!("#memes", channel);
+        assert_eq!(vec!["a", "b", "c d"], rest);
+
+        let msg = Message::try_from("TEST robots #memes")?;
+        let TestRest(_, _, rest) = msg.command().context("Invalid test command.")?;
+
+        assert!(rest.is_empty());
+        assert!(Message::try_from("TEST robots")?
+            .command::<TestRest>()
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_ctcp_action() {
+        assert_eq!(
+            Some(Ctcp("ACTION", Some("waves"))),
+            Ctcp::parse("\x01ACTION waves\x01")
+        );
+        assert_eq!(Some(Ctcp("VERSION", None)), Ctcp::parse("\x01VERSION"));
+    }
+
+    #[test]
+    fn test_parse_ctcp_plain_message() {
+        assert_eq!(None, Ctcp::parse("ACTION waves"));
+        assert_eq!(None, Ctcp::parse("\x01\x01"));
+        assert_eq!(None, Ctcp::parse(""));
+    }
+
+    #[test]
+    fn test_parse_kv_trailing() {
+        let pairs: Vec<_> = parse_kv_trailing("a=1 b=2 c").collect();
+
+        assert_eq!(vec![("a", Some("1")), ("b", Some("2")), ("c", None)], pairs);
+    }
+
+    #[test]
+    fn test_parse_kv_trailing_with_empty_value() {
+        let pairs: Vec<_> = parse_kv_trailing("  duration=2592000,port=6697  preload= ").collect();
+
+        assert_eq!(
+            vec![
+                ("duration", Some("2592000,port=6697")),
+                ("preload", Some(""))
+            ],
+            pairs
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use crate::command::Command;
+use crate::message::Message;
+
+use std::collections::HashMap;
+
+type Handler<'h> = Box<dyn FnMut(&Message) + 'h>;
+
+/// A registry of handlers keyed by command name, allowing messages to be dispatched to
+/// handlers registered at runtime.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate pircolate;
+/// # use pircolate::message;
+/// # use pircolate::command::CommandRegistry;
+/// #
+/// # fn main() {
+/// let mut registry = CommandRegistry::new();
+///
+/// registry.register("PING", |message| println!("PING {}", message));
+/// registry.fallback(|message| println!("Unhandled: {}", message));
+///
+/// # let msg = message::Message::try_from("PING :test.host.com").unwrap();
+/// registry.dispatch(&msg);
+/// # }
+/// ```
+#[derive(Default)]
+pub struct CommandRegistry<'h> {
+    handlers: HashMap<String, Handler<'h>>,
+    fallback: Option<Handler<'h>>,
+}
+
+impl<'h> CommandRegistry<'h> {
+    pub fn new() -> CommandRegistry<'h> {
+        CommandRegistry::default()
+    }
+
+    /// Registers a handler for messages with the given raw command name, such as `PRIVMSG` or `001`.
+    /// Any handler previously registered for the command is replaced.
+    pub fn register(&mut self, command: &str, handler: impl FnMut(&Message) + 'h) {
+        self.handlers.insert(command.to_owned(), Box::new(handler));
+    }
+
+    /// Registers a handler for messages matching the name of the given `Command`.
+    /// Any handler previously registered for the command is replaced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the command has an empty `NAME`, such as a command that matches every numeric,
+    /// as no message would ever be dispatched to its handler.
+    pub fn register_command<T: Command>(&mut self, handler: impl FnMut(&Message) + 'h) {
+        assert!(
+            !T::NAME.is_empty(),
+            "commands without a name cannot be registered"
+        );
+
+        self.register(T::NAME, handler);
+    }
+
+    /// Registers a handler for messages that have no handler registered for their command.
+    pub fn fallback(&mut self, handler: impl FnMut(&Message) + 'h) {
+        self.fallback = Some(Box::new(handler));
+    }
+
+    /// Invokes the handler registered for the command of the message, or the fallback handler
+    /// if there is none. Returns `false` if no handler was invoked.
+    pub fn dispatch(&mut self, message: &Message) -> bool {
+        let handler = match self.handlers.get_mut(message.raw_command()) {
+            Some(handler) => Some(handler),
+            None => self.fallback.as_mut(),
+        };
+
+        match handler {
+            Some(handler) => {
+                handler(message);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_dispatch_to_registered_handler() -> Result<()> {
+        let mut received = Vec::new();
+        let mut unhandled = 0;
+
+        {
+            let mut registry = CommandRegistry::new();
+            registry.register("PRIVMSG", |message| {
+                received.push(
+                    message
+                        .raw_args()
+                        .next_back()
+                        .unwrap_or_default()
+                        .to_owned(),
+                )
+            });
+            registry.fallback(|_| unhandled += 1);
+
+            assert!(registry.dispatch(&Message::try_from("PRIVMSG #memes :hello")?));
+            assert!(registry.dispatch(&Message::try_from("PING :test.host.com")?));
+        }
+
+        assert_eq!(vec!["hello".to_owned()], received);
+        assert_eq!(1, unhandled);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dispatch_without_handler() -> Result<()> {
+        let mut registry = CommandRegistry::new();
+        registry.register("PRIVMSG", |_| ());
+
+        assert!(!registry.dispatch(&Message::try_from("PING :test.host.com")?));
+        Ok(())
+    }
+
+    struct Unnamed;
+
+    impl Command for Unnamed {
+        const NAME: &'static str = "";
+
+        type Output<'a> = Unnamed;
+
+        fn parse(_: crate::command::ArgumentIter<'_>) -> Option<Unnamed> {
+            Some(Unnamed)
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "commands without a name cannot be registered")]
+    fn test_register_command_without_name() {
+        let mut registry = CommandRegistry::new();
+        registry.register_command::<Unnamed>(|_| ());
+    }
+}
@@ -1,5 +1,6 @@
 use super::*;
-use crate::{command, expand_param};
+use crate::isupport::{self, PrefixModes, ServerFeatures};
+use crate::{command, expand_param, owned_command};
 
 command! {
     /// Represents a PING command.  The first element is the host.
@@ -62,10 +63,274 @@ command! {
     ("PRIVMSG" => PrivMsg(target, message))
 }
 
+owned_command! {
+    /// An owned version of the `PrivMsg` command.
+    #[derive(Clone, PartialEq, Debug)]
+    (PrivMsg(target, message) => OwnedPrivMsg(String, String))
+}
+
+impl<'a> PrivMsg<'a> {
+    /// Retrieves the first whitespace delimited word of the message, such as `!help` for a
+    /// message of `!help foo bar`. If the message is empty or only whitespace, it returns `None`.
+    pub fn command_word(&self) -> Option<&'a str> {
+        self.1.split_whitespace().next()
+    }
+
+    /// Retrieves the remainder of the message following the first word, such as `foo bar` for a
+    /// message of `!help foo bar`. If there is nothing following the first word, it returns an empty string.
+    pub fn args_after_command(&self) -> &'a str {
+        let message = self.1.trim_start();
+
+        match message.find(char::is_whitespace) {
+            Some(position) => message[position..].trim_start(),
+            None => "",
+        }
+    }
+
+    /// Attempts to parse the message as a CTCP message, such as a `/me` action. If the message
+    /// is not wrapped in `\x01`, it returns `None`.
+    pub fn ctcp(&self) -> Option<Ctcp<'a>> {
+        Ctcp::parse(self.1)
+    }
+}
+
+command! {
+    /// Represents a NOTICE command.  The first element is the target of the notice and
+    /// the second element is the message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate pircolate;
+    /// # use pircolate::message;
+    /// # use pircolate::command::Notice;
+    /// #
+    /// # fn main() {
+    /// # let msg = message::Message::try_from("NOTICE memelord :memes are great").unwrap();
+    /// if let Some(Notice(target, message)) = msg.command::<Notice>() {
+    ///     println!("-{}- {}", target, message);
+    /// }
+    /// # }
+    /// ```
+    ("NOTICE" => Notice(target, message))
+}
+
+impl Notice<'_> {
+    /// Determines whether this is a notice sent by the server during connection registration,
+    /// which targets the `AUTH` or `*` pseudo-targets rather than a nick or channel.
+    pub fn is_auth_notice(&self) -> bool {
+        self.0 == "AUTH" || self.0 == "*"
+    }
+}
+
 command! {
     ("JOIN" => Join(channel))
 }
 
+command! {
+    /// Represents a QUIT command. The element is the optional quit reason.
+    ("QUIT" => Quit(?reason))
+}
+
+command! {
+    /// Represents a PART command. The first element is the comma separated list of channels being
+    /// parted and the second element is the optional part message.
+    ("PART" => Part(channels, ?message))
+}
+
+/// Represents a MODE command. The first element is the target of the mode change (a channel or a nick),
+/// the second element is the optional mode string, and the third element is the parameters following it.
+pub struct Mode<'a>(pub &'a str, pub Option<&'a str>, pub Vec<&'a str>);
+
+impl<'a> Mode<'a> {
+    /// Tokenizes the mode string into a list of changes, where each change is whether the mode is
+    /// being added, the mode character, and the parameter consumed by the mode (if any).
+    /// The default channel modes from RFC2811 are used to determine which modes consume parameters.
+    pub fn changes(&self) -> Vec<(bool, char, Option<&'a str>)> {
+        self.changes_with(&ServerFeatures::default())
+    }
+
+    /// Tokenizes the mode string into a list of changes, using the modes advertised by the server
+    /// to determine which modes consume parameters. Modes applied to a nick never consume parameters,
+    /// except for the server notice mask set alongside `+s` for opers.
+    pub fn changes_with(&self, features: &ServerFeatures) -> Vec<(bool, char, Option<&'a str>)> {
+        let is_channel = self.0.starts_with(['#', '&', '+', '!']);
+        let mut params = self.2.iter();
+        let mut adding = true;
+        let mut changes = Vec::new();
+
+        for mode in self.1.unwrap_or_default().chars() {
+            match mode {
+                '+' => adding = true,
+                '-' => adding = false,
+                _ => {
+                    let param = if is_channel && features.takes_param(mode, adding) {
+                        params.next().copied()
+                    } else if !is_channel && adding && mode == 's' {
+                        // NOTE: The snomask is optional, so it's only consumed when present.
+                        params.next().copied()
+                    } else {
+                        None
+                    };
+
+                    changes.push((adding, mode, param));
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Get the server notice mask set by a user mode change, such as `SOME_MASK` in
+    /// `MODE robot +s SOME_MASK`. If the change doesn't set `+s` with a mask, it returns `None`.
+    pub fn snomask(&self) -> Option<&'a str> {
+        self.changes().into_iter().find_map(|change| match change {
+            (true, 's', param) => param,
+            _ => None,
+        })
+    }
+}
+
+impl Command for Mode<'_> {
+    const NAME: &'static str = "MODE";
+
+    type Output<'a> = Mode<'a>;
+
+    fn parse(mut arguments: ArgumentIter<'_>) -> Option<Mode<'_>> {
+        let target = arguments.next()?;
+        let modes = arguments.next();
+
+        Some(Mode(target, modes, arguments.collect()))
+    }
+}
+
+command! {
+    /// Represents a TOPIC command. The first element is the channel and the second element is the new topic,
+    /// which is only present when the topic is being set rather than queried.
+    ("TOPIC" => Topic(channel, ?topic))
+}
+
+command! {
+    /// Represents a KICK command. The first element is the channel, the second element is the nick
+    /// being kicked and the third element is the optional comment.
+    ("KICK" => Kick(channel, nick, ?comment))
+}
+
+command! {
+    /// Represents an INVITE command. The first element is the nick being invited and the second
+    /// element is the channel they are being invited to.
+    ("INVITE" => Invite(nick, channel))
+}
+
+command! {
+    /// Represents an AWAY command. The element is the away message, which is present when
+    /// setting away status and absent when clearing it.
+    ("AWAY" => Away(?message))
+}
+
+command! {
+    /// Represents a WALLOPS command. The element is the message being broadcast.
+    ("WALLOPS" => Wallops(message))
+}
+
+/// The subcommand of an IRCv3 CAP command.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CapSubcommand {
+    Ls,
+    List,
+    Req,
+    Ack,
+    Nak,
+    New,
+    Del,
+    End,
+}
+
+/// Represents a CAP command sent by the server. The first element is the target (the client's nick,
+/// or `*` before registration), the second element is the subcommand, the third element is whether
+/// this is one of several lines with more to follow, and the fourth element is the raw list of capabilities.
+pub struct Cap<'a>(pub &'a str, pub CapSubcommand, pub bool, pub &'a str);
+
+impl<'a> Cap<'a> {
+    /// Get an iterator over the space separated capabilities of this command.
+    pub fn capabilities(&self) -> impl Iterator<Item = &'a str> {
+        self.3.split_whitespace()
+    }
+}
+
+impl Command for Cap<'_> {
+    const NAME: &'static str = "CAP";
+
+    type Output<'a> = Cap<'a>;
+
+    fn parse(mut arguments: ArgumentIter<'_>) -> Option<Cap<'_>> {
+        let target = arguments.next()?;
+        let subcommand = match arguments.next()? {
+            "LS" => CapSubcommand::Ls,
+            "LIST" => CapSubcommand::List,
+            "REQ" => CapSubcommand::Req,
+            "ACK" => CapSubcommand::Ack,
+            "NAK" => CapSubcommand::Nak,
+            "NEW" => CapSubcommand::New,
+            "DEL" => CapSubcommand::Del,
+            "END" => CapSubcommand::End,
+            _ => return None,
+        };
+
+        // NOTE: Multi-line replies mark every line but the last with a `*` before the capabilities.
+        let (continued, capabilities) = match (arguments.next(), arguments.next()) {
+            (Some("*"), Some(capabilities)) => (true, capabilities),
+            (Some(capabilities), _) => (false, capabilities),
+            (None, _) => (false, ""),
+        };
+
+        Some(Cap(target, subcommand, continued, capabilities))
+    }
+}
+
+/// The status of a REGISTER response from the `draft/account-registration` extension.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RegisterStatus {
+    /// The account was registered.
+    Success,
+    /// The account was created, but must be verified before it can be used.
+    VerificationRequired,
+}
+
+/// Represents a REGISTER response sent by the server. The first element is the status of the registration,
+/// the second element is the account and the third element is the message.
+pub struct Register<'a>(pub RegisterStatus, pub &'a str, pub &'a str);
+
+impl Command for Register<'_> {
+    const NAME: &'static str = "REGISTER";
+
+    type Output<'a> = Register<'a>;
+
+    fn parse(mut arguments: ArgumentIter<'_>) -> Option<Register<'_>> {
+        let status = match arguments.next()? {
+            "SUCCESS" => RegisterStatus::Success,
+            "VERIFICATION_REQUIRED" => RegisterStatus::VerificationRequired,
+            _ => return None,
+        };
+        let account = arguments.next()?;
+        let message = arguments.next()?;
+
+        Some(Register(status, account, message))
+    }
+}
+
+command! {
+    /// Represents an RPL_SNOMASK numeric. The first element is the username, the second element is the
+    /// server notice mask and the third element is the message. Sent when an oper's snomask changes.
+    ("008" => SnoMask(user, mask, message))
+}
+
+command! {
+    /// Represents a WEBIRC command sent by a web gateway on behalf of a client. The elements are the
+    /// gateway password, the gateway name, and the hostname and IP address of the real client.
+    ("WEBIRC" => WebIrc(password, gateway, hostname, ip))
+}
+
 command! {
     /// Represents a WELCOME numeric. The first element is the unsername and the second element is the welcome message.
     ("001" => Welcome(user, message))
@@ -86,90 +351,1109 @@ command! {
     ("004" => ServerInfo(user, message))
 }
 
-#[derive(PartialEq, Debug)]
+/// Represents an RPL_ISON (303) numeric. The first element is the username and the second element is the
+/// space separated list of nicks from the ISON query that are online.
+pub struct IsonReply<'a>(pub &'a str, pub &'a str);
+
+impl<'a> IsonReply<'a> {
+    /// Get an iterator over the nicks that are online.
+    pub fn nicks(&self) -> impl Iterator<Item = &'a str> {
+        self.1.split_whitespace()
+    }
+}
+
+impl Command for IsonReply<'_> {
+    const NAME: &'static str = "303";
+
+    type Output<'a> = IsonReply<'a>;
+
+    fn parse(mut arguments: ArgumentIter<'_>) -> Option<IsonReply<'_>> {
+        let user = arguments.next()?;
+        let nicks = arguments.next().unwrap_or_default();
+
+        Some(IsonReply(user, nicks))
+    }
+}
+
+/// Represents an RPL_MONLIST (732) numeric, which lists nicks on the client's MONITOR list.
+/// The first element is the username and the second element is the comma separated list of nicks.
+pub struct MonList<'a>(pub &'a str, pub &'a str);
+
+impl<'a> MonList<'a> {
+    /// Get an iterator over the monitored nicks.
+    pub fn nicks(&self) -> impl Iterator<Item = &'a str> {
+        self.1.split(',').filter(|nick| !nick.is_empty())
+    }
+}
+
+impl Command for MonList<'_> {
+    const NAME: &'static str = "732";
+
+    type Output<'a> = MonList<'a>;
+
+    fn parse(mut arguments: ArgumentIter<'_>) -> Option<MonList<'_>> {
+        let user = arguments.next()?;
+        let nicks = arguments.next()?;
+
+        Some(MonList(user, nicks))
+    }
+}
+
+command! {
+    /// Represents an RPL_ENDOFMONLIST (733) numeric, which ends the replies to a `MONITOR L` query.
+    /// The first element is the username and the second element is the message.
+    ("733" => EndOfMonList(user, message))
+}
+
+/// Represents a WHOISUSER numeric. The first element is the username, the second element is the nick
+/// being queried, the third element is the user of the nick, the fourth element is its host and the
+/// fifth element is its real name.
+pub struct WhoisUser<'a>(
+    pub &'a str,
+    pub &'a str,
+    pub &'a str,
+    pub &'a str,
+    pub &'a str,
+);
+
+impl Command for WhoisUser<'_> {
+    const NAME: &'static str = "311";
+
+    type Output<'a> = WhoisUser<'a>;
+
+    fn parse(mut arguments: ArgumentIter<'_>) -> Option<WhoisUser<'_>> {
+        let user = arguments.next()?;
+        let nick = arguments.next()?;
+        let username = arguments.next()?;
+        let host = arguments.next()?;
+        // NOTE: The argument before the real name is an unused `*`.
+        let realname = arguments.next_back()?;
+
+        Some(WhoisUser(user, nick, username, host, realname))
+    }
+}
+
+command! {
+    /// Represents a WHOISSERVER numeric. The first element is the username, the second element is the nick
+    /// being queried, the third element is the server the nick is connected to and the fourth element is
+    /// the description of that server.
+    ("312" => WhoisServer(user, nick, server, info))
+}
+
+/// Represents a WHOISIDLE numeric. The first element is the username, the second element is the nick
+/// being queried, the third element is the number of seconds the nick has been idle, the fourth element
+/// is the time the nick connected as a UNIX timestamp and the fifth element is the message. Servers that
+/// don't report when the nick connected omit the fourth element.
+pub struct WhoisIdle<'a>(
+    pub &'a str,
+    pub &'a str,
+    pub u64,
+    pub Option<u64>,
+    pub &'a str,
+);
+
+impl Command for WhoisIdle<'_> {
+    const NAME: &'static str = "317";
+
+    type Output<'a> = WhoisIdle<'a>;
+
+    fn parse(mut arguments: ArgumentIter<'_>) -> Option<WhoisIdle<'_>> {
+        let user = arguments.next()?;
+        let nick = arguments.next()?;
+        let message = arguments.next_back()?;
+        let idle = arguments.next()?.parse().ok()?;
+        let signon = match arguments.next() {
+            Some(signon) => Some(signon.parse().ok()?),
+            None => None,
+        };
+
+        Some(WhoisIdle(user, nick, idle, signon, message))
+    }
+}
+
+command! {
+    /// Represents an ENDOFWHOIS numeric. The first element is the username, the second element is the nick
+    /// that was queried and the third element is the message. Ends the replies to a WHOIS query.
+    ("318" => EndOfWhois(user, nick, message))
+}
+
+command! {
+    /// Represents a WHOISSECURE numeric. The first element is the username, the second element is the nick
+    /// being queried and the third element is the message. Indicates the nick is using a secure connection.
+    ("671" => WhoisSecure(user, nick, message))
+}
+
+command! {
+    /// Represents a WHOISREGNICK numeric. The first element is the username, the second element is the nick
+    /// being queried and the third element is the message. Indicates the nick is registered.
+    ("307" => WhoisRegNick(user, nick, message))
+}
+
+command! {
+    /// Represents a WHOISHELPOP numeric. The first element is the username, the second element is the nick
+    /// being queried and the third element is the message. Indicates the nick is available for help.
+    ("310" => WhoisHelpOp(user, nick, message))
+}
+
+command! {
+    /// Represents a WHOISOPERATOR numeric. The first element is the username, the second element is the nick
+    /// being queried and the third element is the message. Indicates the nick is an IRC operator.
+    ("313" => WhoisOperator(user, nick, message))
+}
+
+command! {
+    /// Represents a WHOISHOST numeric. The first element is the username, the second element is the nick
+    /// being queried and the third element is the message describing the host and IP the nick is connecting from.
+    ("378" => WhoisHost(user, nick, message))
+}
+
+/// Represents a WHOISACTUALLY numeric. The first element is the username, the second element is the nick
+/// being queried, the third element is the actual host of the nick, the fourth element is its actual IP
+/// and the fifth element is the message. Servers that only send the IP omit the host, and servers that only
+/// describe the host in the message omit both.
+pub struct WhoisActually<'a>(
+    pub &'a str,
+    pub &'a str,
+    pub Option<&'a str>,
+    pub Option<&'a str>,
+    pub &'a str,
+);
+
+impl Command for WhoisActually<'_> {
+    const NAME: &'static str = "338";
+
+    type Output<'a> = WhoisActually<'a>;
+
+    fn parse(mut arguments: ArgumentIter<'_>) -> Option<WhoisActually<'_>> {
+        let user = arguments.next()?;
+        let nick = arguments.next()?;
+        let message = arguments.next_back()?;
+        // NOTE: A lone argument before the message is the IP, as the host is only sent alongside it.
+        let (host, ip) = match (arguments.next(), arguments.next()) {
+            (Some(ip), None) => (None, Some(ip)),
+            (host, ip) => (host, ip),
+        };
+
+        Some(WhoisActually(user, nick, host, ip, message))
+    }
+}
+
+command! {
+    /// Represents a SASLMECHS numeric. The first element is the username, the second element is the
+    /// comma separated list of SASL mechanisms supported by the server and the third element is the message.
+    ("908" => SaslMechs(user, mechanisms, message))
+}
+
+impl<'a> SaslMechs<'a> {
+    /// Get an iterator over the SASL mechanisms supported by the server.
+    pub fn mechanisms(&self) -> impl Iterator<Item = &'a str> {
+        self.1.split(',').filter(|mechanism| !mechanism.is_empty())
+    }
+}
+
+command! {
+    /// Represents a TIME numeric. The first element is the username, the second element is the name of the
+    /// server and the third element is the server's local time. The format of the time varies between servers.
+    ("391" => TimeReply(user, server, time))
+}
+
+impl TimeReply<'_> {
+    /// Attempts to find the time of day within the server's local time, as hours, minutes and seconds.
+    /// As servers format the time differently, this looks for the first `HH:MM:SS` token.
+    pub fn clock(&self) -> Option<(u8, u8, u8)> {
+        self.2.split_whitespace().find_map(|token| {
+            let mut parts = token.split(':').map(|part| match part.len() {
+                1 | 2 => part.parse::<u8>().ok(),
+                _ => None,
+            });
+
+            match (parts.next()?, parts.next()?, parts.next()?, parts.next()) {
+                (Some(hour), Some(minute), Some(second), None)
+                    if hour < 24 && minute < 60 && second <= 60 =>
+                {
+                    Some((hour, minute, second))
+                }
+                _ => None,
+            }
+        })
+    }
+}
+
+/// Represents a LOCALUSERS numeric. The first element is the username, the second element is the current
+/// number of local users and the third element is the maximum number of local users.
+pub struct LocalUsers<'a>(pub &'a str, pub u32, pub u32);
+
+impl Command for LocalUsers<'_> {
+    const NAME: &'static str = "265";
+
+    type Output<'a> = LocalUsers<'a>;
+
+    fn parse(arguments: ArgumentIter<'_>) -> Option<LocalUsers<'_>> {
+        let (user, current, max) = parse_user_counts(arguments)?;
+
+        Some(LocalUsers(user, current, max))
+    }
+}
+
+/// Represents a GLOBALUSERS numeric. The first element is the username, the second element is the current
+/// number of global users and the third element is the maximum number of global users.
+pub struct GlobalUsers<'a>(pub &'a str, pub u32, pub u32);
+
+impl Command for GlobalUsers<'_> {
+    const NAME: &'static str = "266";
+
+    type Output<'a> = GlobalUsers<'a>;
+
+    fn parse(arguments: ArgumentIter<'_>) -> Option<GlobalUsers<'_>> {
+        let (user, current, max) = parse_user_counts(arguments)?;
+
+        Some(GlobalUsers(user, current, max))
+    }
+}
+
+fn parse_user_counts(mut arguments: ArgumentIter<'_>) -> Option<(&str, u32, u32)> {
+    let user = arguments.next()?;
+    let message = arguments.next_back()?;
+
+    // NOTE: Some servers send the counts as explicit arguments, while others only
+    // include them in the message, such as "Current local users 5, max 10".
+    let (current, max) = if arguments.len() >= 2 {
+        (arguments.next()?, arguments.next()?)
+    } else {
+        let mut counts = message
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|count| !count.is_empty());
+
+        (counts.next()?, counts.next()?)
+    };
+
+    Some((user, current.parse().ok()?, max.parse().ok()?))
+}
+
+/// Represents an RPL_ISUPPORT (005) reply. The first element is the user and the second element is
+/// the list of `KEY=value` tokens describing server features. Tokens without a value have a value of
+/// `None`, and negated tokens keep their leading `-` as part of the key.
+pub struct ISupport<'a>(pub &'a str, pub Vec<(&'a str, Option<&'a str>)>);
+
+impl<'a> ISupport<'a> {
+    /// Get an iterator over the tokens of this reply as `(key, value)` pairs.
+    pub fn tokens(&self) -> impl Iterator<Item = (&'a str, Option<&'a str>)> + '_ {
+        self.1.iter().copied()
+    }
+}
+
+impl Command for ISupport<'_> {
+    const NAME: &'static str = "005";
+
+    type Output<'a> = ISupport<'a>;
+
+    fn parse(arguments: ArgumentIter<'_>) -> Option<ISupport<'_>> {
+        let user = arguments.clone().next()?;
+
+        Some(ISupport(user, isupport::tokens(arguments).collect()))
+    }
+}
+
+/// Represents any numeric reply that ends with display-only text, such as the lines of the MOTD.
+/// The first element is the numeric code and the second element is the trailing text. As this
+/// matches every numeric, its `NAME` is empty and it cannot be registered by name.
+pub struct TextReply<'a>(pub u16, pub &'a str);
+
+impl Command for TextReply<'_> {
+    const NAME: &'static str = "";
+
+    type Output<'a> = TextReply<'a>;
+
+    fn parse(mut arguments: ArgumentIter<'_>) -> Option<TextReply<'_>> {
+        arguments.next()?;
+        let text = arguments.next_back()?;
+
+        Some(TextReply(0, text))
+    }
+
+    fn try_match<'a>(command: &str, arguments: ArgumentIter<'a>) -> Option<TextReply<'a>> {
+        if !is_numeric(command) {
+            return None;
+        }
+
+        let code = command.parse().ok()?;
+        Self::parse(arguments).map(|TextReply(_, text)| TextReply(code, text))
+    }
+}
+
+/// Represents any error numeric in the 400 to 599 range, such as ERR_NICKNAMEINUSE (433) or
+/// ERR_NOSUCHCHANNEL (403). The first element is the numeric code, the second element is the username,
+/// the third element is the subjects of the error (such as the nick or channel) and the fourth element
+/// is the message. As this matches every error numeric, its `NAME` is empty and it cannot be registered
+/// by name. A specific error can be matched with a literal code, such as `NumericError(433, ..)`.
+pub struct NumericError<'a>(pub u16, pub &'a str, pub Vec<&'a str>, pub &'a str);
+
+impl Command for NumericError<'_> {
+    const NAME: &'static str = "";
+
+    type Output<'a> = NumericError<'a>;
+
+    fn parse(mut arguments: ArgumentIter<'_>) -> Option<NumericError<'_>> {
+        let user = arguments.next()?;
+        let message = arguments.next_back()?;
+
+        Some(NumericError(0, user, arguments.collect(), message))
+    }
+
+    fn try_match<'a>(command: &str, arguments: ArgumentIter<'a>) -> Option<NumericError<'a>> {
+        if !is_numeric(command) {
+            return None;
+        }
+
+        match command.parse() {
+            Ok(code @ 400..=599) => {
+                Self::parse(arguments).map(|NumericError(_, user, subjects, message)| {
+                    NumericError(code, user, subjects, message)
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum NamesReplyChannelType {
     Secret,
     Private,
     Other,
 }
 
-pub struct NamesReply<'a>(pub NamesReplyChannelType, pub &'a str, pub Vec<&'a str>);
+impl OwnedArgument for NamesReplyChannelType {
+    type Owned = NamesReplyChannelType;
+
+    fn to_owned_argument(&self) -> NamesReplyChannelType {
+        *self
+    }
+}
+
+pub struct NamesReply<'a>(pub NamesReplyChannelType, pub &'a str, pub Vec<&'a str>);
+
+impl Command for NamesReply<'_> {
+    const NAME: &'static str = "353";
+
+    type Output<'a> = NamesReply<'a>;
+
+    fn parse(arguments: ArgumentIter<'_>) -> Option<NamesReply<'_>> {
+        // NOTE: Since the first parameter is optional, it's just easier to extract
+        // components in reverse.
+        let mut arguments = arguments.rev();
+
+        let names = arguments.next()?.split_whitespace();
+        let channel = arguments.next()?;
+        let channel_type = match arguments.next() {
+            Some(channel_type) => match channel_type {
+                "@" => NamesReplyChannelType::Secret,
+                "*" => NamesReplyChannelType::Private,
+                _ => NamesReplyChannelType::Other,
+            },
+            None => NamesReplyChannelType::Other,
+        };
+
+        Some(NamesReply(channel_type, channel, names.collect()))
+    }
+}
+
+owned_command! {
+    /// An owned version of the `NamesReply` command.
+    #[derive(Clone, PartialEq, Debug)]
+    (NamesReply(channel_type, channel, names) => OwnedNamesReply(NamesReplyChannelType, String, Vec<String>))
+}
+
+/// A member of a channel, as listed by a NAMES or WHO reply. The user and host are only known
+/// when the server includes them, such as with the `userhost-in-names` capability.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Member<'a> {
+    /// The membership prefixes of the member, such as `@` or `@+` with the `multi-prefix` capability.
+    pub prefixes: &'a str,
+    pub nick: &'a str,
+    pub user: Option<&'a str>,
+    pub host: Option<&'a str>,
+}
+
+impl<'a> Member<'a> {
+    /// Parses a member from an entry of a NAMES reply, such as `@+nick!user@host`, given the
+    /// symbols of the membership prefixes used by the server.
+    fn parse(entry: &'a str, symbols: &[char]) -> Member<'a> {
+        let (prefixes, entry) =
+            entry.split_at(entry.len() - entry.trim_start_matches(symbols).len());
+        let (entry, host) = match entry.split_once('@') {
+            Some((entry, host)) => (entry, Some(host)),
+            None => (entry, None),
+        };
+        let (nick, user) = match entry.split_once('!') {
+            Some((nick, user)) => (nick, Some(user)),
+            None => (entry, None),
+        };
+
+        Member {
+            prefixes,
+            nick,
+            user,
+            host,
+        }
+    }
+}
+
+/// The membership prefixes commonly advertised by the PREFIX token of RPL_ISUPPORT (005), used
+/// when the prefixes advertised by the server aren't known.
+const MEMBER_PREFIXES: [char; 5] = ['~', '&', '@', '%', '+'];
+
+/// A trait implemented by replies that list the members of a channel, so that a client can
+/// populate its list of users the same way from any of them.
+pub trait Members<'a> {
+    /// Get an iterator over the members listed by this reply, recognizing the membership prefixes
+    /// commonly used by servers (`~&@%+`).
+    fn members(&self) -> impl Iterator<Item = Member<'a>> + '_;
+
+    /// Get an iterator over the members listed by this reply, recognizing the membership prefixes
+    /// advertised by the server through the PREFIX token.
+    fn members_with<'p>(
+        &'p self,
+        prefix_modes: &'p PrefixModes,
+    ) -> impl Iterator<Item = Member<'a>> + 'p;
+}
+
+impl<'a> NamesReply<'a> {
+    fn members_with_symbols<'p>(
+        &'p self,
+        symbols: &'p [char],
+    ) -> impl Iterator<Item = Member<'a>> + 'p {
+        self.2
+            .iter()
+            .map(move |entry| Member::parse(entry, symbols))
+    }
+}
+
+impl<'a> Members<'a> for NamesReply<'a> {
+    fn members(&self) -> impl Iterator<Item = Member<'a>> + '_ {
+        self.members_with_symbols(&MEMBER_PREFIXES)
+    }
+
+    fn members_with<'p>(
+        &'p self,
+        prefix_modes: &'p PrefixModes,
+    ) -> impl Iterator<Item = Member<'a>> + 'p {
+        self.members_with_symbols(&prefix_modes.symbols)
+    }
+}
+
+/// Represents an RPL_WHOREPLY (352) numeric, which describes one user matched by a WHO query.
+/// The trailing parameter holding the hopcount and the real name is split into separate fields.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WhoReply<'a> {
+    /// The channel the user was matched in, or `*` if the query wasn't for a channel.
+    pub channel: &'a str,
+    pub user: &'a str,
+    pub host: &'a str,
+    pub server: &'a str,
+    pub nick: &'a str,
+    /// The status flags of the user, such as `H@` for a channel operator who is here (not away).
+    pub flags: &'a str,
+    pub hopcount: u32,
+    pub realname: &'a str,
+}
+
+impl Command for WhoReply<'_> {
+    const NAME: &'static str = "352";
+
+    type Output<'a> = WhoReply<'a>;
+
+    fn parse(mut arguments: ArgumentIter<'_>) -> Option<WhoReply<'_>> {
+        arguments.next()?;
+        let channel = arguments.next()?;
+        let user = arguments.next()?;
+        let host = arguments.next()?;
+        let server = arguments.next()?;
+        let nick = arguments.next()?;
+        let flags = arguments.next()?;
+        let trailing = arguments.next()?;
+        let (hopcount, realname) = trailing.split_once(' ').unwrap_or((trailing, ""));
+
+        Some(WhoReply {
+            channel,
+            user,
+            host,
+            server,
+            nick,
+            flags,
+            hopcount: hopcount.parse().ok()?,
+            realname,
+        })
+    }
+}
+
+impl<'a> WhoReply<'a> {
+    fn member_with_symbols(&self, symbols: &[char]) -> Member<'a> {
+        // NOTE: The flags start with H or G for here or gone, followed by `*` for opers, and
+        // then the membership prefixes of the user in the channel.
+        let flags = self.flags.trim_start_matches(['H', 'G']);
+        let flags = flags.strip_prefix('*').unwrap_or(flags);
+        let prefixes = &flags[..flags.len() - flags.trim_start_matches(symbols).len()];
+
+        Member {
+            prefixes,
+            nick: self.nick,
+            user: Some(self.user),
+            host: Some(self.host),
+        }
+    }
+}
+
+impl<'a> Members<'a> for WhoReply<'a> {
+    fn members(&self) -> impl Iterator<Item = Member<'a>> + '_ {
+        std::iter::once(self.member_with_symbols(&MEMBER_PREFIXES))
+    }
+
+    fn members_with<'p>(
+        &'p self,
+        prefix_modes: &'p PrefixModes,
+    ) -> impl Iterator<Item = Member<'a>> + 'p {
+        std::iter::once(self.member_with_symbols(&prefix_modes.symbols))
+    }
+}
+
+pub struct EndNamesReply<'a>(pub &'a str, pub &'a str);
+
+impl Command for EndNamesReply<'_> {
+    const NAME: &'static str = "366";
+
+    type Output<'a> = EndNamesReply<'a>;
+
+    fn parse(arguments: ArgumentIter<'_>) -> Option<EndNamesReply<'_>> {
+        // NOTE: Some servers are bad and include non-standard args at the start.
+        // So the parameters are extracted in reverse to compensate.
+        let mut arguments = arguments.rev();
+
+        let message = arguments.next()?;
+        let channel = arguments.next()?;
+
+        Some(EndNamesReply(channel, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_match;
+    use crate::message::Message;
+    use crate::whois::WhoisAccumulator;
+    use anyhow::{Context, Result};
+
+    #[test]
+    fn test_ping_command() -> Result<()> {
+        let message: Message = Message::try_from("PING :test.host.com")?;
+        let Ping(host) = message.command().context("Invalid ping command.")?;
+
+        assert_eq!("test.host.com", host);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pong_command() -> Result<()> {
+        let message: Message = Message::try_from("PONG :test.host.com")?;
+        let Pong(host) = message.command().context("Invalid pong command.")?;
+
+        assert_eq!("test.host.com", host);
+        Ok(())
+    }
+
+    #[test]
+    fn test_privmsg_command() -> Result<()> {
+        let message: Message = Message::try_from("PRIVMSG #channel :This is a message!")?;
+        let PrivMsg(target, message) = message.command().context("Invalid privmsg command.")?;
+
+        assert_eq!("#channel", target);
+        assert_eq!("This is a message!", message);
+        Ok(())
+    }
+
+    #[test]
+    fn test_notice_command() -> Result<()> {
+        let message: Message = Message::try_from(":nick!u@h NOTICE #chan :hi")?;
+        let Notice(target, message) = message.command().context("Invalid notice command.")?;
+
+        assert_eq!("#chan", target);
+        assert_eq!("hi", message);
+        Ok(())
+    }
+
+    #[test]
+    fn test_auth_notice_command() -> Result<()> {
+        let message: Message =
+            Message::try_from(":irc.memes.com NOTICE AUTH :*** Looking up your hostname")?;
+        let notice: Notice = message.command().context("Invalid notice command.")?;
+
+        assert_eq!("AUTH", notice.0);
+        assert_eq!("*** Looking up your hostname", notice.1);
+        assert!(notice.is_auth_notice());
+        Ok(())
+    }
+
+    #[test]
+    fn test_auth_notice_command_with_star_target() -> Result<()> {
+        let message: Message =
+            Message::try_from(":irc.memes.com NOTICE * :*** Found your hostname")?;
+        let notice: Notice = message.command().context("Invalid notice command.")?;
+
+        assert_eq!("*** Found your hostname", notice.1);
+        assert!(notice.is_auth_notice());
+        Ok(())
+    }
+
+    #[test]
+    fn test_non_auth_notice_command() -> Result<()> {
+        let message: Message = Message::try_from(":nick!u@h NOTICE #chan :hi")?;
+        let notice: Notice = message.command().context("Invalid notice command.")?;
+
+        assert!(!notice.is_auth_notice());
+        Ok(())
+    }
+
+    #[test]
+    fn test_quit_command() -> Result<()> {
+        let message: Message = Message::try_from(":nick!u@h QUIT")?;
+        let Quit(reason) = message.command().context("Invalid quit command.")?;
+
+        assert_eq!(None, reason);
+        Ok(())
+    }
+
+    #[test]
+    fn test_quit_command_with_reason() -> Result<()> {
+        let message: Message = Message::try_from(":nick!u@h QUIT :gone")?;
+        let Quit(reason) = message.command().context("Invalid quit command.")?;
+
+        assert_eq!(Some("gone"), reason);
+        Ok(())
+    }
+
+    #[test]
+    fn test_part_command() -> Result<()> {
+        let message: Message = Message::try_from(":nick!u@h PART #a")?;
+        let Part(channels, reason) = message.command().context("Invalid part command.")?;
+
+        assert_eq!("#a", channels);
+        assert_eq!(None, reason);
+        Ok(())
+    }
+
+    #[test]
+    fn test_part_command_with_message() -> Result<()> {
+        let message: Message = Message::try_from(":nick!u@h PART #a,#b :bye")?;
+        let Part(channels, reason) = message.command().context("Invalid part command.")?;
+
+        assert_eq!("#a,#b", channels);
+        assert_eq!(Some("bye"), reason);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_command() -> Result<()> {
+        let message: Message = Message::try_from(":op!u@h MODE #chan +o-v nick1 nick2")?;
+        let mode: Mode = message.command().context("Invalid mode command.")?;
+
+        assert_eq!("#chan", mode.0);
+        assert_eq!(
+            vec![(true, 'o', Some("nick1")), (false, 'v', Some("nick2"))],
+            mode.changes()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_command_with_mixed_params() -> Result<()> {
+        let message: Message = Message::try_from(":op!u@h MODE #chan +ntk-l+b key *!*@spam.com")?;
+        let mode: Mode = message.command().context("Invalid mode command.")?;
+
+        assert_eq!(
+            vec![
+                (true, 'n', None),
+                (true, 't', None),
+                (true, 'k', Some("key")),
+                (false, 'l', None),
+                (true, 'b', Some("*!*@spam.com")),
+            ],
+            mode.changes()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_user_mode_command() -> Result<()> {
+        let message: Message = Message::try_from(":robot MODE robot :+iw")?;
+        let mode: Mode = message.command().context("Invalid mode command.")?;
+
+        assert_eq!("robot", mode.0);
+        assert_eq!(vec![(true, 'i', None), (true, 'w', None)], mode.changes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_user_mode_command_with_snomask() -> Result<()> {
+        let message: Message = Message::try_from(":robot MODE robot +os +cCfk")?;
+        let mode: Mode = message.command().context("Invalid mode command.")?;
+
+        assert_eq!(
+            vec![(true, 'o', None), (true, 's', Some("+cCfk"))],
+            mode.changes()
+        );
+        assert_eq!(Some("+cCfk"), mode.snomask());
+
+        let message: Message = Message::try_from(":robot MODE robot +s")?;
+        let mode: Mode = message.command().context("Invalid mode command.")?;
+
+        assert_eq!(vec![(true, 's', None)], mode.changes());
+        assert_eq!(None, mode.snomask());
+        Ok(())
+    }
+
+    #[test]
+    fn test_snomask_command() -> Result<()> {
+        let message: Message =
+            Message::try_from(":irc.example.com 008 robot +cCfk :Server notice mask")?;
+        let SnoMask(user, mask, _) = message.command().context("Invalid snomask reply.")?;
+
+        assert_eq!("robot", user);
+        assert_eq!("+cCfk", mask);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_command_query() -> Result<()> {
+        let message: Message = Message::try_from("MODE #chan")?;
+        let mode: Mode = message.command().context("Invalid mode command.")?;
+
+        assert_eq!("#chan", mode.0);
+        assert_eq!(None, mode.1);
+        assert!(mode.changes().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_topic_command_query() -> Result<()> {
+        let message: Message = Message::try_from("TOPIC #chan")?;
+        let Topic(channel, topic) = message.command().context("Invalid topic command.")?;
+
+        assert_eq!("#chan", channel);
+        assert_eq!(None, topic);
+        Ok(())
+    }
+
+    #[test]
+    fn test_topic_command_set() -> Result<()> {
+        let message: Message = Message::try_from(":op!u@h TOPIC #chan :New topic")?;
+        let Topic(channel, topic) = message.command().context("Invalid topic command.")?;
+
+        assert_eq!("#chan", channel);
+        assert_eq!(Some("New topic"), topic);
+        Ok(())
+    }
+
+    #[test]
+    fn test_kick_command() -> Result<()> {
+        let message: Message = Message::try_from(":op!u@h KICK #chan baduser :spam")?;
+        let Kick(channel, nick, comment) = message.command().context("Invalid kick command.")?;
+
+        assert_eq!("#chan", channel);
+        assert_eq!("baduser", nick);
+        assert_eq!(Some("spam"), comment);
+        Ok(())
+    }
+
+    #[test]
+    fn test_kick_command_without_comment() -> Result<()> {
+        let message: Message = Message::try_from(":op!u@h KICK #chan baduser")?;
+        let Kick(channel, nick, comment) = message.command().context("Invalid kick command.")?;
+
+        assert_eq!("#chan", channel);
+        assert_eq!("baduser", nick);
+        assert_eq!(None, comment);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invite_command() -> Result<()> {
+        let message: Message = Message::try_from(":op!u@h INVITE newbie #secret")?;
+        let Invite(nick, channel) = message.command().context("Invalid invite command.")?;
+
+        assert_eq!("newbie", nick);
+        assert_eq!("#secret", channel);
+        Ok(())
+    }
+
+    #[test]
+    fn test_away_command() -> Result<()> {
+        let message: Message = Message::try_from(":nick!u@h AWAY :Gone fishing")?;
+        let Away(reason) = message.command().context("Invalid away command.")?;
+
+        assert_eq!(Some("Gone fishing"), reason);
+        Ok(())
+    }
+
+    #[test]
+    fn test_away_command_cleared() -> Result<()> {
+        let message: Message = Message::try_from(":nick!u@h AWAY")?;
+        let Away(reason) = message.command().context("Invalid away command.")?;
+
+        assert_eq!(None, reason);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wallops_command() -> Result<()> {
+        let message: Message = Message::try_from(":oper!u@h WALLOPS :server going down")?;
+        let Wallops(message) = message.command().context("Invalid wallops command.")?;
+
+        assert_eq!("server going down", message);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cap_command_without_capabilities() -> Result<()> {
+        let message: Message = Message::try_from("CAP * LS")?;
+        let cap: Cap = message.command().context("Invalid cap command.")?;
+
+        assert_eq!("*", cap.0);
+        assert_eq!(CapSubcommand::Ls, cap.1);
+        assert!(!cap.2);
+        assert_eq!(0, cap.capabilities().count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cap_command_ack() -> Result<()> {
+        let message: Message = Message::try_from(":irc.memes.com CAP nick ACK :sasl")?;
+        let cap: Cap = message.command().context("Invalid cap command.")?;
+
+        assert_eq!("nick", cap.0);
+        assert_eq!(CapSubcommand::Ack, cap.1);
+        assert!(!cap.2);
+        assert_eq!(vec!["sasl"], cap.capabilities().collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cap_command_multi_line_ls() -> Result<()> {
+        let message: Message =
+            Message::try_from(":irc.memes.com CAP * LS * :multi-prefix sasl=PLAIN,EXTERNAL")?;
+        let cap: Cap = message.command().context("Invalid cap command.")?;
+
+        assert_eq!(CapSubcommand::Ls, cap.1);
+        assert!(cap.2);
+        assert_eq!(
+            vec!["multi-prefix", "sasl=PLAIN,EXTERNAL"],
+            cap.capabilities().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_priv_msg_ctcp_action() -> Result<()> {
+        let action = Message::try_from(":robot!r@memes.com PRIVMSG #memes :\x01ACTION waves\x01")?;
+        let plain = Message::try_from(":robot!r@memes.com PRIVMSG #memes :waves")?;
+
+        let action: PrivMsg = action.command().context("Invalid privmsg command.")?;
+        let plain: PrivMsg = plain.command().context("Invalid privmsg command.")?;
+
+        assert_eq!(Some(Ctcp("ACTION", Some("waves"))), action.ctcp());
+        assert_eq!(None, plain.ctcp());
+        Ok(())
+    }
+
+    #[test]
+    fn test_isupport_command() -> Result<()> {
+        let msg = Message::try_from(
+            ":irc.example.com 005 robots AWAYLEN=200 CHANTYPES=# EXCEPTS NETWORK=Memes -KNOCK :are supported by this server",
+        )?;
+        let isupport = msg.command::<ISupport>().context("Invalid ISUPPORT.")?;
+        let tokens: Vec<_> = isupport.tokens().collect();
+
+        assert_eq!("robots", isupport.0);
+        assert_eq!(
+            vec![
+                ("AWAYLEN", Some("200")),
+                ("CHANTYPES", Some("#")),
+                ("EXCEPTS", None),
+                ("NETWORK", Some("Memes")),
+                ("-KNOCK", None),
+            ],
+            tokens
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_isupport_command_without_trailing_text() -> Result<()> {
+        let msg = Message::try_from("005 robots EXCEPTS= MODES=4")?;
+        let ISupport(_, tokens) = msg.command::<ISupport>().context("Invalid ISUPPORT.")?;
+
+        assert_eq!(vec![("EXCEPTS", None), ("MODES", Some("4"))], tokens);
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_reply_motd_lines() -> Result<()> {
+        let start =
+            Message::try_from(":irc.memes.com 375 robot :- irc.memes.com Message of the day -")?;
+        let line = Message::try_from(":irc.memes.com 372 robot :- memes for all")?;
+
+        let TextReply(code, text) = start.command().context("Invalid text reply.")?;
+        assert_eq!(375, code);
+        assert_eq!("- irc.memes.com Message of the day -", text);
+
+        let TextReply(code, text) = line.command().context("Invalid text reply.")?;
+        assert_eq!(372, code);
+        assert_eq!("- memes for all", text);
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_reply_uncommon_numerics() -> Result<()> {
+        let cases = [
+            ("042 robot 0PNAAAAAB :your unique ID", 42, "your unique ID"),
+            (
+                "396 robot memes/robot :is now your displayed host",
+                396,
+                "is now your displayed host",
+            ),
+            (
+                "908 robot PLAIN,EXTERNAL :are available SASL mechanisms",
+                908,
+                "are available SASL mechanisms",
+            ),
+            ("999 robot :non-standard", 999, "non-standard"),
+        ];
 
-impl Command for NamesReply<'_> {
-    const NAME: &'static str = "353";
+        for (line, expected_code, expected_text) in cases {
+            let msg = Message::try_from(line)?;
+            let TextReply(code, text) = msg.command().context("Invalid text reply.")?;
 
-    type Output<'a> = NamesReply<'a>;
+            assert_eq!(expected_code, code);
+            assert_eq!(expected_text, text);
+        }
 
-    fn parse(arguments: ArgumentIter<'_>) -> Option<NamesReply<'_>> {
-        // NOTE: Since the first parameter is optional, it's just easier to extract
-        // components in reverse.
-        let mut arguments = arguments.rev();
+        Ok(())
+    }
 
-        let names = arguments.next()?.split_whitespace();
-        let channel = arguments.next()?;
-        let channel_type = match arguments.next() {
-            Some(channel_type) => match channel_type {
-                "@" => NamesReplyChannelType::Secret,
-                "*" => NamesReplyChannelType::Private,
-                _ => NamesReplyChannelType::Other,
-            },
-            None => NamesReplyChannelType::Other,
+    #[test]
+    fn test_numeric_error_nick_in_use() -> Result<()> {
+        let msg = Message::try_from(":irc.example.com 433 * overlord :Nickname is already in use")?;
+        let NumericError(code, user, subjects, message) =
+            msg.command().context("Invalid numeric error.")?;
+
+        assert_eq!(433, code);
+        assert_eq!("*", user);
+        assert_eq!(vec!["overlord"], subjects);
+        assert_eq!("Nickname is already in use", message);
+        Ok(())
+    }
+
+    #[test]
+    fn test_numeric_error_no_such_channel() -> Result<()> {
+        let msg = Message::try_from(":irc.example.com 403 robots #memes :No such channel")?;
+
+        let subject = command_match! {
+            &msg => {
+                NumericError(433, _, _, _) => None,
+                NumericError(403, _, subjects, _) => subjects.first().copied(),
+                _ => None
+            }
         };
 
-        Some(NamesReply(channel_type, channel, names.collect()))
+        assert_eq!(Some("#memes"), subject);
+        Ok(())
     }
-}
 
-pub struct EndNamesReply<'a>(pub &'a str, pub &'a str);
+    #[test]
+    fn test_numeric_error_requires_error_range() -> Result<()> {
+        let welcome = Message::try_from("001 robots :Welcome to the memes")?;
+        let privmsg = Message::try_from("PRIVMSG #memes :433")?;
 
-impl Command for EndNamesReply<'_> {
-    const NAME: &'static str = "366";
+        assert!(welcome.command::<NumericError>().is_none());
+        assert!(privmsg.command::<NumericError>().is_none());
+        Ok(())
+    }
 
-    type Output<'a> = EndNamesReply<'a>;
+    #[test]
+    fn test_text_reply_requires_numeric() -> Result<()> {
+        let nick_only = Message::try_from("372 robot")?;
+        let privmsg = Message::try_from("PRIVMSG #memes :memes")?;
 
-    fn parse(arguments: ArgumentIter<'_>) -> Option<EndNamesReply<'_>> {
-        // NOTE: Some servers are bad and include non-standard args at the start.
-        // So the parameters are extracted in reverse to compensate.
-        let mut arguments = arguments.rev();
+        assert!(nick_only.command::<TextReply>().is_none());
+        assert!(privmsg.command::<TextReply>().is_none());
+        Ok(())
+    }
 
-        let message = arguments.next()?;
-        let channel = arguments.next()?;
+    #[test]
+    fn test_register_success() -> Result<()> {
+        let msg: Message =
+            Message::try_from(":irc.memes.com REGISTER SUCCESS robot :Account created")?;
+        let Register(status, account, message) =
+            msg.command().context("Invalid register command.")?;
 
-        Some(EndNamesReply(channel, message))
+        assert_eq!(RegisterStatus::Success, status);
+        assert_eq!("robot", account);
+        assert_eq!("Account created", message);
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::message::Message;
-    use anyhow::{Context, Result};
+    #[test]
+    fn test_register_verification_required() -> Result<()> {
+        let msg: Message = Message::try_from(
+            ":irc.memes.com REGISTER VERIFICATION_REQUIRED robot :Check your email",
+        )?;
+        let Register(status, _, _) = msg.command().context("Invalid register command.")?;
+
+        assert_eq!(RegisterStatus::VerificationRequired, status);
+        Ok(())
+    }
 
     #[test]
-    fn test_ping_command() -> Result<()> {
-        let message: Message = Message::try_from("PING :test.host.com")?;
-        let Ping(host) = message.command().context("Invalid ping command.")?;
+    fn test_webirc_command() -> Result<()> {
+        let message: Message =
+            Message::try_from("WEBIRC hunter2 kiwiirc user.example.com 192.168.0.1")?;
+        let WebIrc(password, gateway, hostname, ip) =
+            message.command().context("Invalid webirc command.")?;
 
-        assert_eq!("test.host.com", host);
+        assert_eq!("hunter2", password);
+        assert_eq!("kiwiirc", gateway);
+        assert_eq!("user.example.com", hostname);
+        assert_eq!("192.168.0.1", ip);
         Ok(())
     }
 
     #[test]
-    fn test_pong_command() -> Result<()> {
-        let message: Message = Message::try_from("PONG :test.host.com")?;
-        let Pong(host) = message.command().context("Invalid pong command.")?;
+    fn test_privmsg_command_word() -> Result<()> {
+        let message: Message = Message::try_from("PRIVMSG #channel :!help foo bar")?;
+        let privmsg: PrivMsg = message.command().context("Invalid privmsg command.")?;
 
-        assert_eq!("test.host.com", host);
+        assert_eq!(Some("!help"), privmsg.command_word());
+        assert_eq!("foo bar", privmsg.args_after_command());
         Ok(())
     }
 
     #[test]
-    fn test_privmsg_command() -> Result<()> {
-        let message: Message = Message::try_from("PRIVMSG #channel :This is a message!")?;
-        let PrivMsg(target, message) = message.command().context("Invalid privmsg command.")?;
+    fn test_privmsg_command_word_with_leading_whitespace() -> Result<()> {
+        let message: Message = Message::try_from("PRIVMSG #channel :   !help  foo")?;
+        let privmsg: PrivMsg = message.command().context("Invalid privmsg command.")?;
 
-        assert_eq!("#channel", target);
-        assert_eq!("This is a message!", message);
+        assert_eq!(Some("!help"), privmsg.command_word());
+        assert_eq!("foo", privmsg.args_after_command());
+        Ok(())
+    }
+
+    #[test]
+    fn test_privmsg_command_word_with_empty_message() -> Result<()> {
+        let message: Message = Message::try_from("PRIVMSG #channel :")?;
+        let privmsg: PrivMsg = message.command().context("Invalid privmsg command.")?;
+
+        assert_eq!(None, privmsg.command_word());
+        assert_eq!("", privmsg.args_after_command());
         Ok(())
     }
 
@@ -218,6 +1502,302 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_ison_reply_command() -> Result<()> {
+        let msg: Message = Message::try_from(":irc.example.com 303 robots :overlord robot")?;
+        let reply: IsonReply = msg.command().context("Invalid ison reply command.")?;
+
+        assert_eq!("robots", reply.0);
+        assert_eq!(vec!["overlord", "robot"], reply.nicks().collect::<Vec<_>>());
+
+        let msg: Message = Message::try_from(":irc.example.com 303 robots :")?;
+        let reply: IsonReply = msg.command().context("Invalid ison reply command.")?;
+
+        assert_eq!(0, reply.nicks().count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mon_list_command() -> Result<()> {
+        let msg: Message =
+            Message::try_from(":irc.example.com 732 robots :overlord,robot,memelord")?;
+        let reply: MonList = msg.command().context("Invalid monitor list command.")?;
+
+        assert_eq!("robots", reply.0);
+        assert_eq!(
+            vec!["overlord", "robot", "memelord"],
+            reply.nicks().collect::<Vec<_>>()
+        );
+
+        let msg: Message = Message::try_from(":irc.example.com 733 robots :End of MONITOR list")?;
+        let EndOfMonList(user, message) = msg.command().context("Invalid end of monitor list.")?;
+
+        assert_eq!("robots", user);
+        assert_eq!("End of MONITOR list", message);
+        Ok(())
+    }
+
+    #[test]
+    fn test_whois_user_command() -> Result<()> {
+        let msg: Message = Message::try_from("311 robots overlord o memes.com * :The Overlord")?;
+        let WhoisUser(username, nick, user, host, realname) =
+            msg.command().context("Invalid whois user command.")?;
+
+        assert_eq!("robots", username);
+        assert_eq!("overlord", nick);
+        assert_eq!("o", user);
+        assert_eq!("memes.com", host);
+        assert_eq!("The Overlord", realname);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_whois_idle_command() -> Result<()> {
+        let msg: Message =
+            Message::try_from("317 robots overlord 42 1700000000 :seconds idle, signon time")?;
+        let WhoisIdle(_, nick, idle, signon, _) =
+            msg.command().context("Invalid whois idle command.")?;
+
+        assert_eq!("overlord", nick);
+        assert_eq!(42, idle);
+        assert_eq!(Some(1700000000), signon);
+
+        let msg: Message = Message::try_from("317 robots overlord 42 :seconds idle")?;
+        let WhoisIdle(_, _, idle, signon, _) =
+            msg.command().context("Invalid whois idle command.")?;
+
+        assert_eq!(42, idle);
+        assert_eq!(None, signon);
+        assert!(
+            Message::try_from("317 robots overlord forever :seconds idle")?
+                .command::<WhoisIdle>()
+                .is_none()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_whois_secure_command() -> Result<()> {
+        let msg: Message = Message::try_from("671 robots overlord :is using a secure connection")?;
+        let WhoisSecure(username, nick, message) =
+            msg.command().context("Invalid whois secure command.")?;
+
+        assert_eq!("robots", username);
+        assert_eq!("overlord", nick);
+        assert_eq!("is using a secure connection", message);
+
+        let mut whois = WhoisAccumulator::default();
+        whois.apply(&msg);
+        assert!(whois.get("overlord").is_some_and(|info| info.secure));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_whois_reg_nick_command() -> Result<()> {
+        let msg: Message = Message::try_from("307 robots overlord :is a registered nick")?;
+        let WhoisRegNick(username, nick, message) =
+            msg.command().context("Invalid whois reg nick command.")?;
+
+        assert_eq!("robots", username);
+        assert_eq!("overlord", nick);
+        assert_eq!("is a registered nick", message);
+
+        let mut whois = WhoisAccumulator::default();
+        whois.apply(&msg);
+        assert!(whois.get("overlord").is_some_and(|info| info.registered));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_whois_operator_command() -> Result<()> {
+        let msg: Message = Message::try_from("313 robots overlord :is an IRC operator")?;
+        let WhoisOperator(username, nick, message) =
+            msg.command().context("Invalid whois operator command.")?;
+
+        assert_eq!("robots", username);
+        assert_eq!("overlord", nick);
+        assert_eq!("is an IRC operator", message);
+        assert!(msg.command::<WhoisHelpOp>().is_none());
+
+        let mut whois = WhoisAccumulator::default();
+        whois.apply(&msg);
+        let info = whois.get("overlord").context("Missing whois info.")?;
+        assert!(info.operator);
+        assert!(!info.helpop);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_whois_host_command() -> Result<()> {
+        let msg: Message = Message::try_from(
+            ":irc.example.com 378 robots overlord :is connecting from *@memes.com 192.0.2.1",
+        )?;
+        let WhoisHost(username, nick, message) =
+            msg.command().context("Invalid whois host command.")?;
+
+        assert_eq!("robots", username);
+        assert_eq!("overlord", nick);
+        assert_eq!("is connecting from *@memes.com 192.0.2.1", message);
+
+        let mut whois = WhoisAccumulator::default();
+        whois.apply(&msg);
+        let info = whois.get("overlord").context("Missing whois info.")?;
+        assert_eq!(
+            Some("is connecting from *@memes.com 192.0.2.1"),
+            info.connecting_from.as_deref()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_whois_actually_command() -> Result<()> {
+        let msg: Message =
+            Message::try_from("338 robots overlord ~o@10.0.0.1 10.0.0.1 :actually using host")?;
+        let WhoisActually(username, nick, host, ip, message) =
+            msg.command().context("Invalid whois actually command.")?;
+
+        assert_eq!("robots", username);
+        assert_eq!("overlord", nick);
+        assert_eq!(Some("~o@10.0.0.1"), host);
+        assert_eq!(Some("10.0.0.1"), ip);
+        assert_eq!("actually using host", message);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_whois_actually_command_with_only_ip() -> Result<()> {
+        let msg: Message = Message::try_from("338 robots overlord 10.0.0.1 :actually using host")?;
+        let WhoisActually(username, nick, host, ip, message) =
+            msg.command().context("Invalid whois actually command.")?;
+
+        assert_eq!("robots", username);
+        assert_eq!("overlord", nick);
+        assert_eq!(None, host);
+        assert_eq!(Some("10.0.0.1"), ip);
+        assert_eq!("actually using host", message);
+
+        let mut whois = WhoisAccumulator::default();
+        whois.apply(&msg);
+        let info = whois.get("overlord").context("Missing whois info.")?;
+        assert_eq!(None, info.actual_host);
+        assert_eq!(Some("10.0.0.1"), info.actual_ip.as_deref());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_whois_actually_command_with_only_message() -> Result<()> {
+        let msg: Message = Message::try_from("338 robots overlord :is actually ~o@10.0.0.1")?;
+        let WhoisActually(_, nick, host, ip, message) =
+            msg.command().context("Invalid whois actually command.")?;
+
+        assert_eq!("overlord", nick);
+        assert_eq!(None, host);
+        assert_eq!(None, ip);
+        assert_eq!("is actually ~o@10.0.0.1", message);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_reply_command() -> Result<()> {
+        let msg: Message =
+            Message::try_from("391 robots irc.memes.com :Mon Jan 1 2024 -- 12:00:00 +00:00")?;
+        let time: TimeReply = msg.command().context("Invalid time reply command.")?;
+
+        assert_eq!("robots", time.0);
+        assert_eq!("irc.memes.com", time.1);
+        assert_eq!("Mon Jan 1 2024 -- 12:00:00 +00:00", time.2);
+        assert_eq!(Some((12, 0, 0)), time.clock());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_reply_command_without_clock() -> Result<()> {
+        let msg: Message = Message::try_from("391 robots irc.memes.com :1704110400")?;
+        let time: TimeReply = msg.command().context("Invalid time reply command.")?;
+
+        assert_eq!(None, time.clock());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_users_command() -> Result<()> {
+        let msg: Message = Message::try_from("265 robots 5 10 :Current local users 5, max 10")?;
+        let LocalUsers(username, current, max) =
+            msg.command().context("Invalid local users command.")?;
+
+        assert_eq!("robots", username);
+        assert_eq!(5, current);
+        assert_eq!(10, max);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_users_command_with_counts_in_message() -> Result<()> {
+        let msg: Message = Message::try_from("265 robots :Current local users: 5  Max: 10")?;
+        let LocalUsers(username, current, max) =
+            msg.command().context("Invalid local users command.")?;
+
+        assert_eq!("robots", username);
+        assert_eq!(5, current);
+        assert_eq!(10, max);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_users_command() -> Result<()> {
+        let msg: Message =
+            Message::try_from("266 robots 1200 4000 :Current global users 1200, max 4000")?;
+        let GlobalUsers(username, current, max) =
+            msg.command().context("Invalid global users command.")?;
+
+        assert_eq!("robots", username);
+        assert_eq!(1200, current);
+        assert_eq!(4000, max);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_users_command_with_counts_in_message() -> Result<()> {
+        let msg: Message = Message::try_from("266 robots :Current global users: 1200  Max: 4000")?;
+        let GlobalUsers(_, current, max) =
+            msg.command().context("Invalid global users command.")?;
+
+        assert_eq!(1200, current);
+        assert_eq!(4000, max);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sasl_mechs_command() -> Result<()> {
+        let msg: Message = Message::try_from(
+            "908 robots PLAIN,EXTERNAL,SCRAM-SHA-256 :are available SASL mechanisms",
+        )?;
+        let sasl_mechs: SaslMechs = msg.command().context("Invalid sasl mechs command.")?;
+
+        assert_eq!("robots", sasl_mechs.0);
+        assert_eq!(
+            vec!["PLAIN", "EXTERNAL", "SCRAM-SHA-256"],
+            sasl_mechs.mechanisms().collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_names_reply_command() -> Result<()> {
         let msg: Message = Message::try_from("353 = #test :robot1 robot2 robot3")?;
@@ -232,4 +1812,173 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_owned_privmsg_command() -> Result<()> {
+        let owned = {
+            let message: Message = Message::try_from("PRIVMSG #channel :This is a message!")?;
+            let privmsg: PrivMsg = message.command().context("Invalid privmsg command.")?;
+            privmsg.to_owned()
+        };
+
+        assert_eq!(
+            OwnedPrivMsg("#channel".to_owned(), "This is a message!".to_owned()),
+            owned
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_owned_names_reply_command() -> Result<()> {
+        let owned = {
+            let msg: Message = Message::try_from("353 = #test :robot1 robot2")?;
+            let names: NamesReply = msg.command().context("Invaid names reply command.")?;
+            names.to_owned()
+        };
+
+        let OwnedNamesReply(channel_type, channel, users) = owned;
+
+        assert_eq!(NamesReplyChannelType::Other, channel_type);
+        assert_eq!("#test", channel);
+        assert_eq!(vec!["robot1".to_owned(), "robot2".to_owned()], users);
+        Ok(())
+    }
+
+    #[test]
+    fn test_end_names_reply_command() -> Result<()> {
+        let msg: Message =
+            Message::try_from(":irc.memes.com 366 robots #memes&[dreams]! :End of /NAMES list.")?;
+        let EndNamesReply(channel, message) =
+            msg.command().context("Invalid end names reply command.")?;
+
+        assert_eq!("#memes&[dreams]!", channel);
+        assert_eq!("End of /NAMES list.", message);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_end_names_reply_for_empty_channel() -> Result<()> {
+        // NOTE: An empty channel is only answered with a 366, without any preceding 353.
+        let msg: Message =
+            Message::try_from(":irc.memes.com 366 robots #empty :End of /NAMES list.")?;
+        let EndNamesReply(channel, _) =
+            msg.command().context("Invalid end names reply command.")?;
+
+        assert_eq!("#empty", channel);
+        assert!(msg.command::<NamesReply>().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_names_reply_members() -> Result<()> {
+        let msg: Message =
+            Message::try_from("353 robots = #memes :@+overlord!o@memes.com robot %mod")?;
+        let reply: NamesReply = msg.command().context("Invalid names reply command.")?;
+        let members: Vec<_> = reply.members().collect();
+
+        assert_eq!(
+            vec![
+                Member {
+                    prefixes: "@+",
+                    nick: "overlord",
+                    user: Some("o"),
+                    host: Some("memes.com"),
+                },
+                Member {
+                    prefixes: "",
+                    nick: "robot",
+                    user: None,
+                    host: None,
+                },
+                Member {
+                    prefixes: "%",
+                    nick: "mod",
+                    user: None,
+                    host: None,
+                },
+            ],
+            members
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_names_reply_members_with_prefix_modes() -> Result<()> {
+        let msg: Message = Message::try_from("353 robots = #memes :!@overlord +robot %mod")?;
+        let reply: NamesReply = msg.command().context("Invalid names reply command.")?;
+        let prefix_modes = PrefixModes::parse("(Yov)!@+").context("Invalid prefix modes.")?;
+        let members: Vec<_> = reply
+            .members_with(&prefix_modes)
+            .map(|member| (member.prefixes, member.nick))
+            .collect();
+        let default_member = reply.members().last().context("Missing member.")?;
+
+        assert_eq!(
+            vec![("!@", "overlord"), ("+", "robot"), ("", "%mod")],
+            members
+        );
+        assert_eq!(("%", "mod"), (default_member.prefixes, default_member.nick));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_who_reply_command() -> Result<()> {
+        let msg: Message = Message::try_from(
+            ":irc.example.com 352 robots #memes ~overlord memes.com irc.example.com overlord H*@ :2 The Meme Overlord",
+        )?;
+        let reply: WhoReply = msg.command().context("Invalid who reply command.")?;
+
+        assert_eq!(
+            WhoReply {
+                channel: "#memes",
+                user: "~overlord",
+                host: "memes.com",
+                server: "irc.example.com",
+                nick: "overlord",
+                flags: "H*@",
+                hopcount: 2,
+                realname: "The Meme Overlord",
+            },
+            reply
+        );
+        assert_eq!(
+            vec![Member {
+                prefixes: "@",
+                nick: "overlord",
+                user: Some("~overlord"),
+                host: Some("memes.com"),
+            }],
+            reply.members().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "@",
+            reply
+                .members_with(&PrefixModes::default())
+                .next()
+                .context("Missing member.")?
+                .prefixes
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_who_reply_without_realname() -> Result<()> {
+        let msg: Message =
+            Message::try_from("352 robots * robot bots.com irc.example.com robot G :0")?;
+        let reply: WhoReply = msg.command().context("Invalid who reply command.")?;
+
+        assert_eq!(0, reply.hopcount);
+        assert_eq!("", reply.realname);
+        assert_eq!(
+            "",
+            reply.members().next().context("Missing member.")?.prefixes
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,45 @@
+//! The dcc module contains helpers for interpreting the arguments of DCC requests,
+//! which are sent as CTCP messages within the body of a PRIVMSG.
+
+use std::net::{Ipv4Addr, SocketAddrV4};
+
+/// Decodes the address and port arguments of a DCC request into a socket address. DCC encodes
+/// an IPv4 address as a big-endian integer written in decimal, such as `3232235521` for
+/// `192.168.0.1`. If either argument is non-numeric or out of range, it returns `None`.
+pub fn decode_address(ip: &str, port: &str) -> Option<SocketAddrV4> {
+    // NOTE: `parse` accepts a leading `+`, which is never valid in a DCC argument.
+    if !is_decimal(ip) || !is_decimal(port) {
+        return None;
+    }
+
+    let ip = Ipv4Addr::from(ip.parse::<u32>().ok()?);
+    let port = port.parse().ok()?;
+
+    Some(SocketAddrV4::new(ip, port))
+}
+
+fn is_decimal(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_address() {
+        assert_eq!(
+            Some(SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 1), 12345)),
+            decode_address("3232235521", "12345")
+        );
+    }
+
+    #[test]
+    fn test_decode_address_with_invalid_input() {
+        assert_eq!(None, decode_address("4294967296", "12345"));
+        assert_eq!(None, decode_address("3232235521", "65536"));
+        assert_eq!(None, decode_address("192.168.0.1", "12345"));
+        assert_eq!(None, decode_address("+3232235521", "12345"));
+        assert_eq!(None, decode_address("3232235521", ""));
+    }
+}
@@ -7,8 +7,25 @@ pub enum MessageParseError {
         #[from]
         source: std::str::Utf8Error,
     },
-    #[error("Unexpected End of Input (malformed message).")]
-    UnexpectedEndOfInput,
+    #[error("Unexpected End of Input at byte {position} (malformed message).")]
+    UnexpectedEndOfInput { position: usize },
+    #[error("Message has tags but no command (input ended at byte {position}).")]
+    MissingCommand { position: usize },
+    #[error("Message is {len} bytes long, exceeding the maximum message length.")]
+    MessageTooLong { len: usize },
+    #[error("Message tags are {len} bytes long, exceeding the maximum tags length.")]
+    TagsTooLong { len: usize },
+    #[error("Illegal character {byte:#04x} at byte {position} of the message.")]
+    IllegalCharacter { byte: u8, position: usize },
+    #[error("Unexpected line break at byte {position}; the input contains more than one line.")]
+    UnexpectedLineBreak { position: usize },
+    #[error("Invalid message component {value:?}.")]
+    InvalidComponent { value: String },
+    #[error("I/O error while reading or writing messages.")]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
 }
 
 pub type MessageParseResult<T> = Result<T, MessageParseError>;
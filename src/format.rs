@@ -0,0 +1,499 @@
+//! The format module contains helpers for applying IRC formatting codes to text,
+//! such as the body of a PRIVMSG.
+//!
+//! Each helper wraps the given text in the control codes for its format and closes
+//! its own scope when done, so helpers may be freely nested. The `strip`, `to_ansi` and
+//! `to_html` functions go the other way, converting formatted text for display outside of IRC.
+
+/// Toggles bold text.
+pub const BOLD: char = '\x02';
+/// Starts or resets a color.
+pub const COLOR: char = '\x03';
+/// Toggles monospace text.
+pub const MONOSPACE: char = '\x11';
+/// Toggles reversed foreground and background colors.
+pub const REVERSE: char = '\x16';
+/// Toggles italic text.
+pub const ITALIC: char = '\x1D';
+/// Toggles strikethrough text.
+pub const STRIKETHROUGH: char = '\x1E';
+/// Toggles underlined text.
+pub const UNDERLINE: char = '\x1F';
+/// Resets all formatting.
+pub const RESET: char = '\x0F';
+
+/// The sixteen standard IRC colors.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Color {
+    White,
+    Black,
+    Blue,
+    Green,
+    Red,
+    Brown,
+    Magenta,
+    Orange,
+    Yellow,
+    LightGreen,
+    Cyan,
+    LightCyan,
+    LightBlue,
+    Pink,
+    Grey,
+    LightGrey,
+}
+
+impl Color {
+    /// The numeric code used to represent this color on the wire.
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// Get the color represented by a numeric code. Codes outside of the sixteen standard colors,
+    /// such as the extended colors or 99 for the default color, return `None`.
+    pub fn from_code(code: u8) -> Option<Color> {
+        COLORS.get(usize::from(code)).copied()
+    }
+
+    /// The ANSI SGR parameter that sets this color as the foreground. Adding 10 sets it as the background.
+    fn ansi(self) -> u8 {
+        [
+            97, 30, 34, 32, 91, 31, 35, 33, 93, 92, 36, 96, 94, 95, 90, 37,
+        ][self as usize]
+    }
+
+    /// The CSS color commonly used by clients to display this color.
+    fn html(self) -> &'static str {
+        [
+            "#FFFFFF", "#000000", "#00007F", "#009300", "#FF0000", "#7F0000", "#9C009C", "#FC7F00",
+            "#FFFF00", "#00FC00", "#009393", "#00FFFF", "#0000FC", "#FF00FF", "#7F7F7F", "#D2D2D2",
+        ][self as usize]
+    }
+}
+
+const COLORS: [Color; 16] = [
+    Color::White,
+    Color::Black,
+    Color::Blue,
+    Color::Green,
+    Color::Red,
+    Color::Brown,
+    Color::Magenta,
+    Color::Orange,
+    Color::Yellow,
+    Color::LightGreen,
+    Color::Cyan,
+    Color::LightCyan,
+    Color::LightBlue,
+    Color::Pink,
+    Color::Grey,
+    Color::LightGrey,
+];
+
+/// Formats the text as bold.
+pub fn bold(text: &str) -> String {
+    toggle(BOLD, text)
+}
+
+/// Formats the text as italic.
+pub fn italic(text: &str) -> String {
+    toggle(ITALIC, text)
+}
+
+/// Formats the text as underlined.
+pub fn underline(text: &str) -> String {
+    toggle(UNDERLINE, text)
+}
+
+/// Formats the text as strikethrough.
+pub fn strikethrough(text: &str) -> String {
+    toggle(STRIKETHROUGH, text)
+}
+
+/// Formats the text as monospace.
+pub fn monospace(text: &str) -> String {
+    toggle(MONOSPACE, text)
+}
+
+/// Formats the text with its foreground and background colors reversed.
+pub fn reverse(text: &str) -> String {
+    toggle(REVERSE, text)
+}
+
+/// Formats the text with the given foreground and optional background color.
+/// Any color reset inside of the text (such as one produced by a nested call to `color`)
+/// restores this color rather than clearing it. The closing color reset is followed by an
+/// empty bold toggle, so that text appended after it can safely start with a digit.
+pub fn color(fg: Color, bg: Option<Color>, text: &str) -> String {
+    let code = match bg {
+        Some(bg) => format!("{}{:02},{:02}", COLOR, fg.code(), bg.code()),
+        None => format!("{}{:02}", COLOR, fg.code()),
+    };
+
+    let mut result = String::with_capacity(text.len() + code.len() * 2);
+    result.push_str(&code);
+
+    // NOTE: Without a background, a leading comma followed by a digit would be read
+    // as a background color, so an empty bold toggle is inserted to separate them.
+    if bg.is_none() && starts_with_background(text) {
+        result.push(BOLD);
+        result.push(BOLD);
+    }
+
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        result.push(c);
+
+        if c == COLOR && !chars.peek().is_some_and(|(_, next)| next.is_ascii_digit()) {
+            result.push_str(&code[COLOR.len_utf8()..]);
+
+            if bg.is_none() && starts_with_background(&text[index + 1..]) {
+                result.push(BOLD);
+                result.push(BOLD);
+            }
+        }
+    }
+
+    // NOTE: A digit appended after a bare reset would be read as a new color, so the reset is
+    // always separated from whatever follows it.
+    result.push(COLOR);
+    result.push(BOLD);
+    result.push(BOLD);
+    result
+}
+
+/// Removes all IRC formatting codes from the text, including the digits of color codes,
+/// leaving only the plain text.
+pub fn strip(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for_each_run(text, |_, run| result.push_str(run));
+
+    result
+}
+
+/// Converts IRC formatting codes within the text into ANSI escape sequences for display in a terminal.
+/// Colors outside of the sixteen standard colors and monospace text have no ANSI equivalent, so they
+/// are dropped, and any formatting still active at the end of the text is reset.
+pub fn to_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut current = Style::default();
+
+    for_each_run(text, |style, run| {
+        if *style != current {
+            let mut params = vec![0];
+
+            for (enabled, param) in [
+                (style.bold, 1),
+                (style.italic, 3),
+                (style.underline, 4),
+                (style.reverse, 7),
+                (style.strikethrough, 9),
+            ] {
+                if enabled {
+                    params.push(param);
+                }
+            }
+
+            params.extend(style.fg.map(Color::ansi));
+            params.extend(style.bg.map(|bg| bg.ansi() + 10));
+
+            let params: Vec<_> = params.iter().map(u8::to_string).collect();
+            result.push_str(&format!("\x1b[{}m", params.join(";")));
+            current = *style;
+        }
+
+        result.push_str(run);
+    });
+
+    if current != Style::default() {
+        result.push_str("\x1b[0m");
+    }
+
+    result
+}
+
+/// Converts IRC formatting codes within the text into HTML, wrapping each formatted run of text in
+/// a `<span>` with an inline style. The text itself is escaped, so the result is safe to embed in a page.
+/// Colors outside of the sixteen standard colors are dropped.
+pub fn to_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for_each_run(text, |style, run| {
+        let mut css = Vec::new();
+
+        if style.bold {
+            css.push("font-weight:bold".to_owned());
+        }
+        if style.italic {
+            css.push("font-style:italic".to_owned());
+        }
+        match (style.underline, style.strikethrough) {
+            (true, true) => css.push("text-decoration:underline line-through".to_owned()),
+            (true, false) => css.push("text-decoration:underline".to_owned()),
+            (false, true) => css.push("text-decoration:line-through".to_owned()),
+            (false, false) => (),
+        }
+        if style.monospace {
+            css.push("font-family:monospace".to_owned());
+        }
+
+        // NOTE: The page's own colors are unknown, so reversed text without explicit colors
+        // falls back to the system colors for the page's background and text.
+        let (fg, bg) = if style.reverse {
+            (
+                Some(style.bg.map_or("Canvas", Color::html)),
+                Some(style.fg.map_or("CanvasText", Color::html)),
+            )
+        } else {
+            (style.fg.map(Color::html), style.bg.map(Color::html))
+        };
+
+        if let Some(fg) = fg {
+            css.push(format!("color:{}", fg));
+        }
+        if let Some(bg) = bg {
+            css.push(format!("background-color:{}", bg));
+        }
+
+        if css.is_empty() {
+            push_escaped_html(&mut result, run);
+        } else {
+            result.push_str(&format!("<span style=\"{}\">", css.join(";")));
+            push_escaped_html(&mut result, run);
+            result.push_str("</span>");
+        }
+    });
+
+    result
+}
+
+/// The formatting applied to a run of text.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strikethrough: bool,
+    monospace: bool,
+    reverse: bool,
+    fg: Option<Color>,
+    bg: Option<Color>,
+}
+
+/// Splits the text into runs of unformatted text, calling `f` with each non-empty run and the
+/// formatting applied to it.
+fn for_each_run(text: &str, mut f: impl FnMut(&Style, &str)) {
+    let mut style = Style::default();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if !matches!(
+            c,
+            BOLD | ITALIC | UNDERLINE | STRIKETHROUGH | MONOSPACE | REVERSE | RESET | COLOR
+        ) {
+            continue;
+        }
+
+        if start < index {
+            f(&style, &text[start..index]);
+        }
+
+        match c {
+            BOLD => style.bold = !style.bold,
+            ITALIC => style.italic = !style.italic,
+            UNDERLINE => style.underline = !style.underline,
+            STRIKETHROUGH => style.strikethrough = !style.strikethrough,
+            MONOSPACE => style.monospace = !style.monospace,
+            REVERSE => style.reverse = !style.reverse,
+            RESET => style = Style::default(),
+            // NOTE: A color code without a foreground resets both colors, and a comma is only
+            // part of the code when a foreground is present and a digit follows it.
+            _ => match take_color_code(&mut chars) {
+                Some(fg) => {
+                    style.fg = Color::from_code(fg);
+
+                    let rest = chars.peek().map_or("", |&(index, _)| &text[index..]);
+                    if starts_with_background(rest) {
+                        chars.next();
+                        style.bg = take_color_code(&mut chars).and_then(Color::from_code);
+                    }
+                }
+                None => {
+                    style.fg = None;
+                    style.bg = None;
+                }
+            },
+        }
+
+        start = chars.peek().map_or(text.len(), |&(index, _)| index);
+    }
+
+    if start < text.len() {
+        f(&style, &text[start..]);
+    }
+}
+
+/// Consumes the one or two digits of a color code, returning the code if any digits were present.
+fn take_color_code(chars: &mut std::iter::Peekable<std::str::CharIndices>) -> Option<u8> {
+    let mut code = None;
+
+    for _ in 0..2 {
+        match chars.peek() {
+            Some(&(_, digit @ '0'..='9')) => {
+                code = Some(code.unwrap_or(0) * 10 + digit as u8 - b'0');
+                chars.next();
+            }
+            _ => break,
+        }
+    }
+
+    code
+}
+
+fn push_escaped_html(result: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(c),
+        }
+    }
+}
+
+fn toggle(code: char, text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+    result.push(code);
+    result.push_str(text);
+    result.push(code);
+    result
+}
+
+fn starts_with_background(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next() == Some(',') && chars.next().is_some_and(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bold() {
+        assert_eq!("\x02memes\x02", bold("memes"));
+    }
+
+    #[test]
+    fn test_color_with_foreground() {
+        assert_eq!("\x0304memes\x03\x02\x02", color(Color::Red, None, "memes"));
+    }
+
+    #[test]
+    fn test_color_with_foreground_and_background() {
+        assert_eq!(
+            "\x0304,01memes\x03\x02\x02",
+            color(Color::Red, Some(Color::Black), "memes")
+        );
+    }
+
+    #[test]
+    fn test_color_with_leading_digit() {
+        assert_eq!("\x03041st\x03\x02\x02", color(Color::Red, None, "1st"));
+    }
+
+    #[test]
+    fn test_color_with_leading_comma_and_digit() {
+        assert_eq!(
+            "\x0304\x02\x02,1\x03\x02\x02",
+            color(Color::Red, None, ",1")
+        );
+    }
+
+    #[test]
+    fn test_color_followed_by_digit() {
+        let text = format!("{}5", color(Color::Red, None, "a"));
+
+        assert_eq!("\x0304a\x03\x02\x025", text);
+        assert_eq!("a5", strip(&text));
+        assert_eq!("<span style=\"color:#FF0000\">a</span>5", to_html(&text));
+    }
+
+    #[test]
+    fn test_strip() {
+        assert_eq!(
+            "hot memes, 1st",
+            strip("\x02\x0304,01hot\x03 memes\x02, \x0312\x1d1st\x0f")
+        );
+        assert_eq!("plain", strip("plain"));
+    }
+
+    #[test]
+    fn test_to_ansi_with_bold_and_color() {
+        assert_eq!(
+            "\x1b[0;1;91;40mhot\x1b[0;1m memes\x1b[0m done",
+            to_ansi("\x02\x0304,01hot\x03 memes\x02 done")
+        );
+    }
+
+    #[test]
+    fn test_to_ansi_with_reset() {
+        assert_eq!(
+            "\x1b[0;4;34mlink\x1b[0m plain",
+            to_ansi("\x1f\x0302link\x0f plain")
+        );
+        assert_eq!("plain", to_ansi("plain"));
+    }
+
+    #[test]
+    fn test_to_html_with_bold_and_color() {
+        assert_eq!(
+            "<span style=\"font-weight:bold;color:#FF0000;background-color:#000000\">hot</span>\
+             <span style=\"font-weight:bold\"> memes</span> done",
+            to_html("\x02\x0304,01hot\x03 memes\x02 done")
+        );
+    }
+
+    #[test]
+    fn test_to_html_escapes_text() {
+        assert_eq!(
+            "<span style=\"font-style:italic\">&lt;b&gt;</span> &amp; more",
+            to_html("\x1d<b>\x0f & more")
+        );
+    }
+
+    #[test]
+    fn test_to_html_with_comma_after_color() {
+        assert_eq!(
+            "<span style=\"color:#00007F\">,x</span>",
+            to_html("\x0302,x")
+        );
+        assert_eq!(
+            "<span style=\"color:#00007F\">1st</span>",
+            to_html(&color(Color::Blue, None, "1st"))
+        );
+    }
+
+    #[test]
+    fn test_nested_bold_in_color() {
+        let text = format!("hello {}", bold("world"));
+
+        assert_eq!(
+            "\x0302hello \x02world\x02\x03\x02\x02",
+            color(Color::Blue, None, &text)
+        );
+    }
+
+    #[test]
+    fn test_nested_color_restores_outer_color() {
+        let text = format!("a{}c", color(Color::Blue, None, "b"));
+
+        assert_eq!(
+            "\x0304,01a\x0302b\x0304,01\x02\x02c\x03\x02\x02",
+            color(Color::Red, Some(Color::Black), &text)
+        );
+    }
+}
@@ -0,0 +1,375 @@
+//! The isupport module contains types for interpreting the features a server
+//! advertises through RPL_ISUPPORT (005) messages.
+
+use crate::casemapping::CaseMapping;
+use crate::command::ArgumentIter;
+use crate::message::Message;
+
+/// The category a channel mode belongs to, as advertised by the CHANMODES token.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChanModeKind {
+    /// Modes that add or remove an entry from a list. They always take a parameter.
+    List,
+    /// Modes that change a setting and always take a parameter.
+    AlwaysParam,
+    /// Modes that change a setting and only take a parameter when being set.
+    SetParam,
+    /// Modes that change a setting and never take a parameter.
+    NoParam,
+}
+
+/// The channel modes supported by a server, grouped by how they consume parameters.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ChanModes {
+    pub list: Vec<char>,
+    pub always_param: Vec<char>,
+    pub set_param: Vec<char>,
+    pub no_param: Vec<char>,
+}
+
+impl ChanModes {
+    /// Parses the value of a CHANMODES token, such as `beI,k,l,imnpst`. Any groups beyond
+    /// the first four are ignored. If there are fewer than four groups, it returns `None`.
+    pub fn parse(value: &str) -> Option<ChanModes> {
+        let mut groups = value.split(',').map(|group| group.chars().collect());
+
+        Some(ChanModes {
+            list: groups.next()?,
+            always_param: groups.next()?,
+            set_param: groups.next()?,
+            no_param: groups.next()?,
+        })
+    }
+
+    /// Determines which category the mode belongs to, if any.
+    pub fn kind(&self, mode: char) -> Option<ChanModeKind> {
+        if self.list.contains(&mode) {
+            Some(ChanModeKind::List)
+        } else if self.always_param.contains(&mode) {
+            Some(ChanModeKind::AlwaysParam)
+        } else if self.set_param.contains(&mode) {
+            Some(ChanModeKind::SetParam)
+        } else if self.no_param.contains(&mode) {
+            Some(ChanModeKind::NoParam)
+        } else {
+            None
+        }
+    }
+
+    /// Determines whether the mode consumes a parameter when being added or removed.
+    pub fn takes_param(&self, mode: char, adding: bool) -> bool {
+        match self.kind(mode) {
+            Some(ChanModeKind::List) | Some(ChanModeKind::AlwaysParam) => true,
+            Some(ChanModeKind::SetParam) => adding,
+            Some(ChanModeKind::NoParam) | None => false,
+        }
+    }
+}
+
+impl Default for ChanModes {
+    /// The channel modes specified by RFC2811, used when a server does not advertise CHANMODES.
+    fn default() -> ChanModes {
+        ChanModes {
+            list: vec!['b', 'e', 'I'],
+            always_param: vec!['k'],
+            set_param: vec!['l'],
+            no_param: vec!['i', 'm', 'n', 'p', 's', 't'],
+        }
+    }
+}
+
+/// The channel membership modes supported by a server and the symbols that represent them,
+/// as advertised by the PREFIX token. The two lists are parallel and ordered from the highest
+/// rank to the lowest.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PrefixModes {
+    pub modes: Vec<char>,
+    pub symbols: Vec<char>,
+}
+
+impl PrefixModes {
+    /// Parses the value of a PREFIX token, such as `(ov)@+`. An empty value means the server has
+    /// no membership modes. If the value is malformed or the lists differ in length, it returns `None`.
+    pub fn parse(value: &str) -> Option<PrefixModes> {
+        if value.is_empty() {
+            return Some(PrefixModes {
+                modes: Vec::new(),
+                symbols: Vec::new(),
+            });
+        }
+
+        let (modes, symbols) = value.strip_prefix('(')?.split_once(')')?;
+        let modes: Vec<char> = modes.chars().collect();
+        let symbols: Vec<char> = symbols.chars().collect();
+
+        if modes.len() != symbols.len() {
+            return None;
+        }
+
+        Some(PrefixModes { modes, symbols })
+    }
+
+    /// Get the symbol representing a membership mode, such as `@` for `o`.
+    pub fn symbol(&self, mode: char) -> Option<char> {
+        let index = self.modes.iter().position(|&m| m == mode)?;
+
+        self.symbols.get(index).copied()
+    }
+
+    /// Get the membership mode represented by a symbol, such as `o` for `@`.
+    pub fn mode(&self, symbol: char) -> Option<char> {
+        let index = self.symbols.iter().position(|&s| s == symbol)?;
+
+        self.modes.get(index).copied()
+    }
+
+    /// Determines whether the mode is a membership mode.
+    pub fn contains(&self, mode: char) -> bool {
+        self.modes.contains(&mode)
+    }
+}
+
+impl Default for PrefixModes {
+    /// The membership modes specified by RFC2811, used when a server does not advertise PREFIX.
+    fn default() -> PrefixModes {
+        PrefixModes {
+            modes: vec!['o', 'v'],
+            symbols: vec!['@', '+'],
+        }
+    }
+}
+
+/// The set of features advertised by a server. Servers typically send several
+/// RPL_ISUPPORT (005) messages, each of which should be applied in turn.
+#[derive(Clone, Debug)]
+pub struct ServerFeatures {
+    pub chan_modes: ChanModes,
+    /// The channel membership modes and their symbols from the PREFIX token.
+    pub prefix_modes: PrefixModes,
+    /// The maximum number of modes with parameters allowed in a single MODE command, from the MODES token.
+    pub modes: usize,
+    /// The casemapping used by the server when comparing nicks and channel names, from the CASEMAPPING token.
+    pub case_mapping: CaseMapping,
+}
+
+impl ServerFeatures {
+    pub fn new() -> ServerFeatures {
+        ServerFeatures::default()
+    }
+
+    /// Determines whether a channel mode consumes a parameter when being added or removed,
+    /// taking into account both the CHANMODES and PREFIX tokens.
+    pub fn takes_param(&self, mode: char, adding: bool) -> bool {
+        self.prefix_modes.contains(mode) || self.chan_modes.takes_param(mode, adding)
+    }
+
+    /// Compares two nicks or channel names for equality under the casemapping advertised by the server.
+    pub fn eq_ignore_case(&self, left: &str, right: &str) -> bool {
+        self.case_mapping.eq_ignore_case(left, right)
+    }
+
+    /// Applies the tokens of an RPL_ISUPPORT (005) message to this set of features.
+    /// Returns `false` and leaves the features unchanged if the message is not an 005.
+    pub fn apply(&mut self, message: &Message) -> bool {
+        if message.raw_command() != "005" {
+            return false;
+        }
+
+        for (key, value) in tokens(message.raw_args()) {
+            match (key, value) {
+                ("CHANMODES", Some(value)) => {
+                    if let Some(chan_modes) = ChanModes::parse(value) {
+                        self.chan_modes = chan_modes;
+                    }
+                }
+                ("-CHANMODES", _) => self.chan_modes = ChanModes::default(),
+                ("PREFIX", value) => {
+                    if let Some(prefix_modes) = PrefixModes::parse(value.unwrap_or_default()) {
+                        self.prefix_modes = prefix_modes;
+                    }
+                }
+                ("-PREFIX", _) => self.prefix_modes = PrefixModes::default(),
+                // NOTE: A MODES token without a value means there is no limit.
+                ("MODES", value) => {
+                    self.modes = match value {
+                        Some(value) => value.parse().unwrap_or(DEFAULT_MODES),
+                        None => usize::MAX,
+                    };
+                }
+                ("-MODES", _) => self.modes = DEFAULT_MODES,
+                ("CASEMAPPING", Some(value)) => {
+                    if let Some(case_mapping) = CaseMapping::parse(value) {
+                        self.case_mapping = case_mapping;
+                    }
+                }
+                ("-CASEMAPPING", _) => self.case_mapping = CaseMapping::default(),
+                _ => (),
+            }
+        }
+
+        true
+    }
+}
+
+impl Default for ServerFeatures {
+    fn default() -> ServerFeatures {
+        ServerFeatures {
+            chan_modes: ChanModes::default(),
+            prefix_modes: PrefixModes::default(),
+            modes: DEFAULT_MODES,
+            case_mapping: CaseMapping::default(),
+        }
+    }
+}
+
+/// The number of modes with parameters allowed per MODE command when a server does not advertise MODES.
+const DEFAULT_MODES: usize = 3;
+
+/// Extracts the `KEY=value` tokens from the arguments of an RPL_ISUPPORT (005) message,
+/// skipping the leading nick and the trailing human-readable text.
+pub(crate) fn tokens(
+    mut arguments: ArgumentIter<'_>,
+) -> impl Iterator<Item = (&str, Option<&str>)> {
+    arguments.next();
+
+    // NOTE: The trailing text is only skipped when present, as some servers omit it.
+    if arguments
+        .clone()
+        .next_back()
+        .is_some_and(|last| last.contains(' '))
+    {
+        arguments.next_back();
+    }
+
+    arguments.map(|token| match token.split_once('=') {
+        Some((key, "")) => (key, None),
+        Some((key, value)) => (key, Some(value)),
+        None => (token, None),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_parse_chan_modes() {
+        let chan_modes = ChanModes::parse("beI,k,l,imnpst").unwrap();
+
+        assert_eq!(vec!['b', 'e', 'I'], chan_modes.list);
+        assert_eq!(vec!['k'], chan_modes.always_param);
+        assert_eq!(vec!['l'], chan_modes.set_param);
+        assert_eq!(vec!['i', 'm', 'n', 'p', 's', 't'], chan_modes.no_param);
+    }
+
+    #[test]
+    fn test_parse_chan_modes_with_missing_groups() {
+        assert_eq!(None, ChanModes::parse("beI,k,l"));
+    }
+
+    #[test]
+    fn test_classify_chan_modes() {
+        let chan_modes = ChanModes::parse("beI,k,l,imnpst").unwrap();
+
+        assert_eq!(Some(ChanModeKind::List), chan_modes.kind('b'));
+        assert_eq!(Some(ChanModeKind::AlwaysParam), chan_modes.kind('k'));
+        assert_eq!(Some(ChanModeKind::SetParam), chan_modes.kind('l'));
+        assert_eq!(Some(ChanModeKind::NoParam), chan_modes.kind('m'));
+        assert_eq!(None, chan_modes.kind('z'));
+
+        assert!(chan_modes.takes_param('b', false));
+        assert!(chan_modes.takes_param('k', false));
+        assert!(chan_modes.takes_param('l', true));
+        assert!(!chan_modes.takes_param('l', false));
+        assert!(!chan_modes.takes_param('m', true));
+    }
+
+    #[test]
+    fn test_parse_prefix_modes() {
+        let prefix_modes = PrefixModes::parse("(ov)@+").unwrap();
+
+        assert_eq!(vec!['o', 'v'], prefix_modes.modes);
+        assert_eq!(vec!['@', '+'], prefix_modes.symbols);
+        assert_eq!(Some('@'), prefix_modes.symbol('o'));
+        assert_eq!(Some('v'), prefix_modes.mode('+'));
+        assert_eq!(None, prefix_modes.symbol('h'));
+        assert_eq!(None, prefix_modes.mode('%'));
+    }
+
+    #[test]
+    fn test_parse_prefix_modes_with_invalid_input() {
+        assert_eq!(None, PrefixModes::parse("ov@+"));
+        assert_eq!(None, PrefixModes::parse("(ohv)@+"));
+        assert!(PrefixModes::parse("").unwrap().modes.is_empty());
+    }
+
+    #[test]
+    fn test_apply_multiple_isupport_messages() -> Result<()> {
+        let mut features = ServerFeatures::new();
+
+        let first =
+            Message::try_from("005 robots AWAYLEN=200 CHANTYPES=# :are supported by this server")?;
+        let second = Message::try_from(
+            "005 robots CHANMODES=eIbq,k,flj,CFLMPQScgimnprstuz :are supported by this server",
+        )?;
+        let other = Message::try_from("001 robots :our overlords")?;
+
+        assert!(features.apply(&first));
+        assert_eq!(ChanModes::default(), features.chan_modes);
+
+        assert!(features.apply(&second));
+        assert!(!features.apply(&other));
+        assert_eq!(vec!['e', 'I', 'b', 'q'], features.chan_modes.list);
+        assert_eq!(Some(ChanModeKind::SetParam), features.chan_modes.kind('j'));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_prefix() -> Result<()> {
+        let mut features = ServerFeatures::new();
+        let msg =
+            Message::try_from("005 robots PREFIX=(qaohv)~&@%+ :are supported by this server")?;
+
+        assert_eq!(PrefixModes::default(), features.prefix_modes);
+        assert!(features.apply(&msg));
+        assert_eq!(vec!['q', 'a', 'o', 'h', 'v'], features.prefix_modes.modes);
+        assert_eq!(vec!['~', '&', '@', '%', '+'], features.prefix_modes.symbols);
+        assert!(features.takes_param('h', false));
+        assert!(features.takes_param('l', true));
+        assert!(!features.takes_param('m', true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_modes() -> Result<()> {
+        let mut features = ServerFeatures::new();
+        let limited = Message::try_from("005 robots MODES=4 :are supported by this server")?;
+        let unlimited = Message::try_from("005 robots MODES :are supported by this server")?;
+
+        assert_eq!(3, features.modes);
+        assert!(features.apply(&limited));
+        assert_eq!(4, features.modes);
+        assert!(features.apply(&unlimited));
+        assert_eq!(usize::MAX, features.modes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_case_mapping() -> Result<()> {
+        let mut features = ServerFeatures::new();
+        let msg = Message::try_from("005 robots CASEMAPPING=ascii :are supported by this server")?;
+
+        assert_eq!(CaseMapping::Rfc1459, features.case_mapping);
+        assert!(features.eq_ignore_case("#Memes[1]", "#memes{1}"));
+        assert!(features.apply(&msg));
+        assert_eq!(CaseMapping::Ascii, features.case_mapping);
+        assert!(!features.eq_ignore_case("#Memes[1]", "#memes{1}"));
+        assert!(features.eq_ignore_case("#Memes[1]", "#memes[1]"));
+
+        Ok(())
+    }
+}
@@ -1,7 +1,14 @@
+pub mod casemapping;
 pub mod command;
+pub mod dcc;
 pub mod error;
+pub mod format;
+pub mod isupport;
 pub mod message;
+pub mod queue;
 pub mod tag;
+#[cfg(feature = "twitch-client")]
+pub mod whois;
 
 // pub use command::Command;
 pub use message::Message;
@@ -0,0 +1,281 @@
+use crate::error::MessageParseError;
+use crate::message::Message;
+use crate::tag;
+
+use std::fmt;
+
+/// A builder for composing messages from their constituent parts, rather than by
+/// formatting a raw IRC line by hand.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate pircolate;
+/// # use pircolate::message::MessageBuilder;
+/// #
+/// # fn main() {
+/// let msg = MessageBuilder::new()
+///     .tag("msgid", "123")
+///     .command("PRIVMSG")
+///     .arg("#memes")
+///     .trailing("memes for all")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!("@msgid=123 PRIVMSG #memes :memes for all", msg.raw_message());
+/// # }
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct MessageBuilder {
+    tags: Vec<(String, String)>,
+    prefix: Option<String>,
+    command: String,
+    arguments: Vec<String>,
+    trailing: Option<String>,
+}
+
+impl MessageBuilder {
+    pub fn new() -> MessageBuilder {
+        MessageBuilder::default()
+    }
+
+    /// Adds a tag to the message. The value is escaped when the message is built,
+    /// and an empty value results in a tag without a value.
+    pub fn tag(mut self, key: &str, value: &str) -> MessageBuilder {
+        self.tags.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Sets the prefix of the message, either from its raw form such as `nick!user@host` or from a `Prefix`.
+    pub fn prefix(mut self, prefix: impl fmt::Display) -> MessageBuilder {
+        self.prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Sets the command of the message, such as `PRIVMSG` or `001`.
+    pub fn command(mut self, command: &str) -> MessageBuilder {
+        self.command = command.to_owned();
+        self
+    }
+
+    /// Adds an argument to the message. Only the final argument of a message may be empty,
+    /// contain spaces, or start with a `:`.
+    pub fn arg(mut self, argument: &str) -> MessageBuilder {
+        self.arguments.push(argument.to_owned());
+        self
+    }
+
+    /// Sets the trailing argument of the message, which always follows any other arguments
+    /// and is always written with a leading `:`.
+    pub fn trailing(mut self, trailing: &str) -> MessageBuilder {
+        self.trailing = Some(trailing.to_owned());
+        self
+    }
+
+    /// Assembles the message into its wire format and parses it into a `Message`.
+    pub fn build(self) -> Result<Message, MessageParseError> {
+        let mut line = String::new();
+
+        if !self.tags.is_empty() {
+            line.push('@');
+
+            for (index, (key, value)) in self.tags.iter().enumerate() {
+                if key.is_empty() || key.contains(['=', ';', ' ']) || has_illegal_character(key) {
+                    return Err(invalid_component(key));
+                }
+
+                if index > 0 {
+                    line.push(';');
+                }
+
+                line.push_str(key);
+
+                if !value.is_empty() {
+                    line.push('=');
+                    line.push_str(&tag::escape(value));
+                }
+            }
+
+            line.push(' ');
+        }
+
+        if let Some(prefix) = &self.prefix {
+            if prefix.is_empty() || prefix.contains(' ') || has_illegal_character(prefix) {
+                return Err(invalid_component(prefix));
+            }
+
+            line.push(':');
+            line.push_str(prefix);
+            line.push(' ');
+        }
+
+        if self.command.is_empty()
+            || self.command.contains(' ')
+            || has_illegal_character(&self.command)
+        {
+            return Err(invalid_component(&self.command));
+        }
+
+        line.push_str(&self.command);
+
+        let last = self.arguments.len().saturating_sub(1);
+        for (index, argument) in self.arguments.iter().enumerate() {
+            if has_illegal_character(argument) {
+                return Err(invalid_component(argument));
+            }
+
+            line.push(' ');
+
+            if needs_colon(argument) {
+                if index != last || self.trailing.is_some() {
+                    return Err(invalid_component(argument));
+                }
+
+                line.push(':');
+            }
+
+            line.push_str(argument);
+        }
+
+        if let Some(trailing) = &self.trailing {
+            if has_illegal_character(trailing) {
+                return Err(invalid_component(trailing));
+            }
+
+            line.push_str(" :");
+            line.push_str(trailing);
+        }
+
+        Message::try_from(line)
+    }
+}
+
+/// Determines whether an argument must be written as a trailing argument with a leading `:`.
+pub(crate) fn needs_colon(argument: &str) -> bool {
+    argument.is_empty() || argument.contains(' ') || argument.starts_with(':')
+}
+
+fn has_illegal_character(value: &str) -> bool {
+    value.contains(['\r', '\n', '\0'])
+}
+
+fn invalid_component(value: &str) -> MessageParseError {
+    MessageParseError::InvalidComponent {
+        value: value.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Prefix;
+    use anyhow::Result;
+
+    #[test]
+    fn test_build_tagged_privmsg() -> Result<()> {
+        let msg = MessageBuilder::new()
+            .tag("msgid", "abc")
+            .tag("+draft/reply", "")
+            .prefix("robot!robot@memes.com")
+            .command("PRIVMSG")
+            .arg("#memes")
+            .trailing("memes for all")
+            .build()?;
+
+        let tags: Vec<_> = msg.raw_tags().collect();
+        let args: Vec<_> = msg.raw_args().collect();
+
+        assert_eq!(vec![("msgid", Some("abc")), ("+draft/reply", None)], tags);
+        assert_eq!(
+            Some(("robot", Some("robot"), Some("memes.com"))),
+            msg.prefix()
+        );
+        assert_eq!("PRIVMSG", msg.raw_command());
+        assert_eq!(vec!["#memes", "memes for all"], args);
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_with_typed_prefix() -> Result<()> {
+        let prefix = Prefix::User {
+            nick: "foo",
+            user: Some("bar"),
+            host: Some("baz.com"),
+        };
+        let msg = MessageBuilder::new()
+            .prefix(prefix)
+            .command("PING")
+            .build()?;
+
+        assert_eq!(":foo!bar@baz.com PING", msg.raw_message());
+        assert_eq!(Some(prefix), msg.prefix_parsed());
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_escapes_tag_values() -> Result<()> {
+        let msg = MessageBuilder::new()
+            .tag("a", "b; c\\d")
+            .command("TAGMSG")
+            .build()?;
+
+        assert_eq!("@a=b\\:\\sc\\\\d TAGMSG", msg.raw_message());
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_adds_colon_to_final_argument_when_needed() -> Result<()> {
+        let spaced = MessageBuilder::new()
+            .command("TEST")
+            .arg("a")
+            .arg("b c")
+            .build()?;
+        let empty = MessageBuilder::new().command("TEST").arg("").build()?;
+        let colon = MessageBuilder::new().command("TEST").arg(":)").build()?;
+        let plain = MessageBuilder::new().command("TEST").arg("a").build()?;
+
+        assert_eq!("TEST a :b c", spaced.raw_message());
+        assert_eq!("TEST :", empty.raw_message());
+        assert_eq!("TEST ::)", colon.raw_message());
+        assert_eq!("TEST a", plain.raw_message());
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_middle_argument() {
+        let result = MessageBuilder::new()
+            .command("TEST")
+            .arg("a b")
+            .arg("c")
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::InvalidComponent { .. })
+        ));
+    }
+
+    #[test]
+    fn test_build_rejects_line_breaks() {
+        let result = MessageBuilder::new()
+            .command("PRIVMSG")
+            .arg("#memes")
+            .trailing("hello\r\nQUIT")
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::InvalidComponent { .. })
+        ));
+    }
+
+    #[test]
+    fn test_build_rejects_missing_command() {
+        let result = MessageBuilder::new().arg("a").build();
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::InvalidComponent { .. })
+        ));
+    }
+}
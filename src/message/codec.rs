@@ -0,0 +1,167 @@
+//! The codec module contains a `tokio_util` codec for reading and writing framed messages.
+
+use crate::error::MessageParseError;
+use crate::message::{Message, MAX_MESSAGE_LENGTH, MAX_TAGS_LENGTH};
+
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// A codec that frames messages on line endings, parsing each line into a `Message` when decoding
+/// and writing each message followed by a CRLF when encoding. Lines may end with either LF or CRLF,
+/// and empty lines are skipped. Lines exceeding the maximum message and tags lengths are rejected, and
+/// the rest of an oversized line is discarded so that decoding resumes with the line after it.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct IrcCodec {
+    /// Whether the rest of an oversized line is being discarded, up to its line ending.
+    discarding: bool,
+}
+
+impl IrcCodec {
+    pub fn new() -> IrcCodec {
+        IrcCodec::default()
+    }
+}
+
+impl Decoder for IrcCodec {
+    type Item = Message;
+    type Error = MessageParseError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Message>, MessageParseError> {
+        loop {
+            let Some(position) = src.iter().position(|&byte| byte == b'\n') else {
+                if self.discarding {
+                    src.clear();
+                    return Ok(None);
+                }
+
+                // NOTE: A partial line that can no longer fit within the limits will never be valid,
+                // so it is dropped along with the rest of the line rather than buffered forever.
+                if src.len() > MAX_TAGS_LENGTH + MAX_MESSAGE_LENGTH {
+                    let len = src.len();
+
+                    src.clear();
+                    self.discarding = true;
+                    return Err(MessageParseError::MessageTooLong { len });
+                }
+
+                return Ok(None);
+            };
+
+            let line = src.split_to(position + 1);
+
+            if self.discarding {
+                self.discarding = false;
+                continue;
+            }
+
+            let line = line.strip_suffix(b"\n").unwrap_or(&line);
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+            if !line.is_empty() {
+                return Message::try_from_checked(line).map(Some);
+            }
+        }
+    }
+}
+
+impl Encoder<Message> for IrcCodec {
+    type Error = MessageParseError;
+
+    fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<(), MessageParseError> {
+        dst.extend_from_slice(&item.to_wire_bytes());
+        Ok(())
+    }
+}
+
+impl Encoder<&Message> for IrcCodec {
+    type Error = MessageParseError;
+
+    fn encode(&mut self, item: &Message, dst: &mut BytesMut) -> Result<(), MessageParseError> {
+        dst.extend_from_slice(&item.to_wire_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_decode_partial_messages() -> Result<()> {
+        let mut codec = IrcCodec::new();
+        let mut buffer = BytesMut::from("PING :a\r\n:robot PRIVMSG #memes :memes\r\n\r\nPONG");
+
+        let first = codec.decode(&mut buffer)?.unwrap();
+        let second = codec.decode(&mut buffer)?.unwrap();
+
+        assert_eq!("PING :a", first.raw_message());
+        assert_eq!(":robot PRIVMSG #memes :memes", second.raw_message());
+        assert!(codec.decode(&mut buffer)?.is_none());
+        assert_eq!(b"PONG", &buffer[..]);
+
+        buffer.extend_from_slice(b" :b\n");
+        let third = codec.decode(&mut buffer)?.unwrap();
+
+        assert_eq!("PONG :b", third.raw_message());
+        assert!(buffer.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_rejects_long_lines() {
+        let mut codec = IrcCodec::new();
+        let mut complete =
+            BytesMut::from(format!("PRIVMSG #memes :{}\r\n", "a".repeat(500)).as_str());
+        let mut partial = BytesMut::from(
+            "a".repeat(MAX_TAGS_LENGTH + MAX_MESSAGE_LENGTH + 1)
+                .as_str(),
+        );
+
+        assert!(matches!(
+            codec.decode(&mut complete),
+            Err(MessageParseError::MessageTooLong { .. })
+        ));
+        assert!(matches!(
+            codec.decode(&mut partial),
+            Err(MessageParseError::MessageTooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_recovers_after_long_line() -> Result<()> {
+        let mut codec = IrcCodec::new();
+        let mut buffer = BytesMut::from(
+            "a".repeat(MAX_TAGS_LENGTH + MAX_MESSAGE_LENGTH + 1)
+                .as_str(),
+        );
+
+        assert!(matches!(
+            codec.decode(&mut buffer),
+            Err(MessageParseError::MessageTooLong { .. })
+        ));
+        assert!(buffer.is_empty());
+
+        buffer.extend_from_slice(b"aaaa");
+        assert!(codec.decode(&mut buffer)?.is_none());
+
+        buffer.extend_from_slice(b"aaaa\r\nPING :a\r\n");
+        let message = codec.decode(&mut buffer)?.unwrap();
+
+        assert_eq!("PING :a", message.raw_message());
+        assert!(buffer.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode() -> Result<()> {
+        let mut codec = IrcCodec::new();
+        let mut buffer = BytesMut::new();
+
+        codec.encode(Message::try_from("PING :a")?, &mut buffer)?;
+        codec.encode(&Message::try_from("PONG :b\r\n")?, &mut buffer)?;
+
+        assert_eq!(b"PING :a\r\nPONG :b\r\n", &buffer[..]);
+        Ok(())
+    }
+}
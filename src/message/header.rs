@@ -0,0 +1,145 @@
+use crate::command::{ArgumentIter, Command};
+use crate::message::{parser, Message};
+use crate::tag::TagIter;
+
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
+
+/// A message whose tags, prefix and command have been parsed, but whose arguments are only parsed
+/// when first accessed. This is produced by `Message::parse_header_only` for routers that peek at the
+/// command of every message but only need the arguments of a few. Once the arguments are needed
+/// in full, `into_message` converts it into a regular `Message`.
+#[derive(Debug)]
+pub struct MessageHeader {
+    /// The message without its arguments.
+    message: Message,
+    arguments_start: usize,
+    arguments: OnceLock<Option<Arc<[Range<usize>]>>>,
+}
+
+impl MessageHeader {
+    pub(crate) fn new(message: Message, arguments_start: usize) -> MessageHeader {
+        MessageHeader {
+            message,
+            arguments_start,
+            arguments: OnceLock::new(),
+        }
+    }
+
+    /// A strongly typed interface for determining the type of the command
+    /// and retrieving the values of the command. This parses the arguments.
+    pub fn command<'a, T>(&'a self) -> Option<T>
+    where
+        T: Command<Output<'a> = T>,
+    {
+        <T as Command>::try_match(self.raw_command(), self.raw_args())
+    }
+
+    /// Get the raw IRC command this message was constructed from.
+    pub fn raw_message(&self) -> &str {
+        self.message.raw_message()
+    }
+
+    /// Get the raw command associated with this message.
+    pub fn raw_command(&self) -> &str {
+        self.message.raw_command()
+    }
+
+    /// Get the name, user and host of the prefix of this message, if there is one.
+    pub fn prefix(&self) -> Option<(&str, Option<&str>, Option<&str>)> {
+        self.message.prefix()
+    }
+
+    /// Get an iterator to the raw key/value pairs of tags associated with this message.
+    pub fn raw_tags(&self) -> TagIter<'_> {
+        self.message.raw_tags()
+    }
+
+    /// Get an iterator to the raw arguments associated with this message, parsing them first
+    /// if they haven't been already.
+    pub fn raw_args(&self) -> ArgumentIter<'_> {
+        let ranges = self.arguments.get_or_init(|| self.parse_arguments());
+
+        ArgumentIter::new(
+            self.raw_message(),
+            ranges.as_deref().unwrap_or_default().iter(),
+        )
+    }
+
+    /// Converts this into a regular `Message`, parsing its arguments if they haven't been already.
+    pub fn into_message(self) -> Message {
+        let MessageHeader {
+            message,
+            arguments_start,
+            arguments,
+        } = self;
+        let arguments = arguments
+            .into_inner()
+            .unwrap_or_else(|| parser::parse_arguments(message.raw_message(), arguments_start));
+
+        Message {
+            arguments,
+            ..message
+        }
+    }
+
+    fn parse_arguments(&self) -> Option<Arc<[Range<usize>]>> {
+        parser::parse_arguments(self.raw_message(), self.arguments_start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    const MESSAGES: [&str; 5] = [
+        "TEST",
+        "TEST a b :c d",
+        "@a=b :robot!r@memes.com PRIVMSG #memes :memes for all",
+        ":irc.memes.com 005 robot CHANTYPES=# :are supported",
+        "TEST :",
+    ];
+
+    #[test]
+    fn test_lazy_args_match_eager_parse() -> Result<()> {
+        for message in MESSAGES.iter() {
+            let eager = Message::try_from(*message)?;
+            let lazy = Message::parse_header_only(*message)?;
+
+            assert_eq!(eager.raw_command(), lazy.raw_command());
+            assert_eq!(eager.prefix(), lazy.prefix());
+            assert!(eager.raw_tags().eq(lazy.raw_tags()));
+            assert!(lazy.arguments.get().is_none());
+            assert_eq!(
+                eager.raw_args().collect::<Vec<_>>(),
+                lazy.raw_args().collect::<Vec<_>>()
+            );
+            assert!(lazy.arguments.get().is_some());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_into_message_matches_eager_parse() -> Result<()> {
+        for message in MESSAGES.iter() {
+            let eager = Message::try_from(*message)?;
+            let unparsed = Message::parse_header_only(*message)?.into_message();
+            let parsed = {
+                let header = Message::parse_header_only(*message)?;
+                header.raw_args().count();
+                header.into_message()
+            };
+
+            assert_eq!(eager, unparsed);
+            assert_eq!(eager, parsed);
+            assert_eq!(
+                eager.raw_args().collect::<Vec<_>>(),
+                unparsed.raw_args().collect::<Vec<_>>()
+            );
+        }
+
+        Ok(())
+    }
+}
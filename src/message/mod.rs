@@ -4,22 +4,51 @@
 //! The module also contains several constructor methods for constructing
 //! messages to be sent to the server.
 
+mod builder;
+#[cfg(feature = "tokio-codec")]
+pub mod codec;
+mod header;
+mod multiline;
 mod parser;
+#[cfg(feature = "serde")]
+mod serialize;
 
 #[cfg(feature = "twitch-client")]
 mod twitch;
 #[cfg(feature = "twitch-client")]
 pub use twitch::*;
 
-use crate::command::{ArgumentIter, Command};
+pub use builder::MessageBuilder;
+pub use header::MessageHeader;
+pub use multiline::{reassemble_multiline, reassemble_multiline_with_ranges, MULTILINE_CONCAT_TAG};
+pub use parser::{ParseBuffers, ParsedRef};
+
+use crate::casemapping::CaseMapping;
+use crate::command::{self, ArgumentIter, Command, Ctcp};
 use crate::error::MessageParseError;
-use crate::tag::{Tag, TagIter};
+use crate::format;
+use crate::isupport;
+use crate::tag::{self, Tag, TagIter};
 
+use bytes::{Bytes, BytesMut};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::iter::Rev;
 use std::ops::Range;
+use std::str::FromStr;
 use std::sync::Arc;
 
 type MesssageParseResult = Result<Message, MessageParseError>;
 
+/// The maximum length in bytes of a message, excluding tags but including the trailing CRLF.
+pub const MAX_MESSAGE_LENGTH: usize = 512;
+
+/// The maximum length in bytes of the tags of a message, including the leading `@` and trailing space.
+pub const MAX_TAGS_LENGTH: usize = 8191;
+
 #[derive(Clone)]
 struct PrefixRange {
     raw_prefix: Range<usize>,
@@ -30,15 +59,42 @@ struct PrefixRange {
 
 type TagRange = (Range<usize>, Option<Range<usize>>);
 
+/// The source of a message, which is either a server or a user.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Prefix<'a> {
+    /// A server, such as `irc.memes.com`.
+    Server(&'a str),
+    /// A user, such as `nick!user@host`, where the user and host are optional.
+    User {
+        nick: &'a str,
+        user: Option<&'a str>,
+        host: Option<&'a str>,
+    },
+}
+
 /// Representation of IRC messages that splits a message into its constituent
 /// parts specified in RFC1459 and the IRCv3 spec.
 #[derive(Clone)]
 pub struct Message {
     message: Arc<str>,
+    /// The span of this message within the buffer, which may be shared with other messages.
+    span: Range<usize>,
     tags: Option<Arc<[TagRange]>>,
     prefix: Option<PrefixRange>,
     command: Range<usize>,
     arguments: Option<Arc<[Range<usize>]>>,
+    /// The line number and byte offset of the message within the buffer it was parsed from, if
+    /// it was parsed by `parse_lines`.
+    source: Option<(usize, usize)>,
+}
+
+/// The buffer of a message, shared with the `Bytes` returned by `Message::to_wire_bytes`.
+struct SharedBuffer(Arc<str>);
+
+impl AsRef<[u8]> for SharedBuffer {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
 }
 
 impl Message {
@@ -79,9 +135,25 @@ impl Message {
         }
     }
 
+    /// Retrieves the prefix for this message, if there is one, classified as either a server or a user.
+    /// A prefix with a user or host is always a user, otherwise it is a server if it contains a `.`.
+    pub fn prefix_parsed(&self) -> Option<Prefix<'_>> {
+        let (name, user, host) = self.prefix()?;
+
+        if user.is_none() && host.is_none() && name.contains('.') {
+            Some(Prefix::Server(name))
+        } else {
+            Some(Prefix::User {
+                nick: name,
+                user,
+                host,
+            })
+        }
+    }
+
     /// Get an iterator to the raw key/value pairs of tags associated with
     /// this message.
-    pub fn raw_tags(&self) -> TagIter {
+    pub fn raw_tags(&self) -> TagIter<'_> {
         if let Some(ref tags) = self.tags {
             TagIter::new(self.raw_message(), tags.iter())
         } else {
@@ -89,6 +161,44 @@ impl Message {
         }
     }
 
+    /// Get an iterator to the raw key/value pairs of the client-only tags associated with
+    /// this message. Client-only tags are prefixed with `+` and should not be trusted.
+    pub fn client_tags(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.raw_tags().filter(|(key, _)| tag::is_client_only(key))
+    }
+
+    /// Get an iterator to the raw key/value pairs of the server tags associated with this
+    /// message, excluding any client-only tags.
+    pub fn server_tags(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.raw_tags().filter(|(key, _)| !tag::is_client_only(key))
+    }
+
+    /// Attempt to get the raw value of the tag with the given key. The outer `Option` is whether the
+    /// tag is present and the inner `Option` is whether it has a value.
+    pub fn get_tag(&self, key: &str) -> Option<Option<&str>> {
+        self.raw_tags().find_value(key)
+    }
+
+    /// Collects the raw key/value pairs of the tags associated with this message into a map.
+    /// If a key appears more than once, the last value wins.
+    pub fn tags_map(&self) -> HashMap<&str, Option<&str>> {
+        self.raw_tags().collect()
+    }
+
+    /// Compares the tags of this message with the tags of another message as maps, ignoring
+    /// their order. Values are compared after unescaping, a tag without a value is treated as
+    /// having an empty value, and the last occurrence of a duplicated key wins.
+    pub fn tags_equal(&self, other: &Message) -> bool {
+        self.tag_map() == other.tag_map()
+    }
+
+    /// The tags of this message as a map of keys to unescaped values, as compared by `tags_equal`.
+    fn tag_map(&self) -> HashMap<&str, Cow<'_, str>> {
+        self.raw_tags()
+            .map(|(key, value)| (key, tag::unescape(value.unwrap_or_default())))
+            .collect()
+    }
+
     /// Attempt to get the raw prefix value associated with this message.
     pub fn raw_prefix(&self) -> Option<&str> {
         if let Some(ref prefix_range) = self.prefix {
@@ -100,22 +210,313 @@ impl Message {
 
     /// Retrieve the raw command associated with this message.
     pub fn raw_command(&self) -> &str {
-        &self.message[self.command.clone()]
+        &self.raw_message()[self.command.clone()]
     }
 
     /// Get an iterator to the raw arguments associated with this message.
-    pub fn raw_args(&self) -> ArgumentIter {
-        if let Some(ref arguments) = self.arguments {
-            ArgumentIter::new(self.raw_message(), arguments.iter())
+    pub fn raw_args(&self) -> ArgumentIter<'_> {
+        ArgumentIter::new(self.raw_message(), self.argument_ranges().iter())
+    }
+
+    /// Get the argument at the given index, such as the nick targeted by a numeric reply at index 1.
+    /// If the message has fewer arguments than the index, it returns `None`.
+    pub fn arg(&self, index: usize) -> Option<&str> {
+        self.raw_args().get(index)
+    }
+
+    /// Get the underlying bytes of the argument at the given index, which is convenient for network
+    /// code that works with byte buffers. If the message has fewer arguments than the index, it returns `None`.
+    pub fn arg_bytes(&self, index: usize) -> Option<&[u8]> {
+        let range = self.argument_ranges().get(index)?;
+
+        Some(&self.raw_message().as_bytes()[range.clone()])
+    }
+
+    /// The ranges of the arguments of this message within its span.
+    fn argument_ranges(&self) -> &[Range<usize>] {
+        self.arguments.as_deref().unwrap_or_default()
+    }
+
+    /// Get an iterator to the raw arguments associated with this message in reverse order.
+    /// This is useful for commands whose leading arguments are optional or non-standard,
+    /// where it's easier to extract arguments starting from the end.
+    pub fn raw_args_rev(&self) -> Rev<ArgumentIter<'_>> {
+        self.raw_args().rev()
+    }
+
+    /// Attempt to get the code of a numeric reply, such as `1` for `001`. If the command is not
+    /// a numeric, it returns `None`. Typed commands still match numerics by their string form.
+    pub fn numeric(&self) -> Option<u16> {
+        if command::is_numeric(self.raw_command()) {
+            self.raw_command().parse().ok()
         } else {
-            ArgumentIter::new(self.raw_message(), [].iter())
+            None
         }
     }
 
+    /// Reconstructs the argument portion of this message as it would appear on the wire, with the
+    /// final argument prefixed by `:` when it is empty, contains spaces, or starts with a `:`.
+    pub fn args_joined(&self) -> String {
+        let mut joined = String::new();
+        let mut arguments = self.raw_args().peekable();
+
+        while let Some(argument) = arguments.next() {
+            if !joined.is_empty() {
+                joined.push(' ');
+            }
+
+            if arguments.peek().is_none() && builder::needs_colon(argument) {
+                joined.push(':');
+            }
+
+            joined.push_str(argument);
+        }
+
+        joined
+    }
+
+    /// Get an iterator to the raw arguments of a numeric reply, with the leading argument (the
+    /// client's own nick) skipped. If the command is not a numeric, it returns `None`.
+    pub fn numeric_args(&self) -> Option<ArgumentIter<'_>> {
+        if !command::is_numeric(self.raw_command()) {
+            return None;
+        }
+
+        let mut arguments = self.raw_args();
+        arguments.next();
+
+        Some(arguments)
+    }
+
+    /// Retrieves the network name from the NETWORK token of an RPL_ISUPPORT (005) message,
+    /// without interpreting any of the other tokens. If this is not an 005 message or it has
+    /// no NETWORK token, it returns `None`.
+    pub fn network_name(&self) -> Option<&str> {
+        if self.raw_command() != "005" {
+            return None;
+        }
+
+        isupport::tokens(self.raw_args())
+            .find(|&(key, _)| key == "NETWORK")
+            .and_then(|(_, value)| value)
+    }
+
     /// Get the raw IRC command this message was constrcuted from.
     #[inline]
     pub fn raw_message(&self) -> &str {
-        &self.message
+        &self.message[self.span.clone()]
+    }
+
+    /// Renders this message as a complete IRC line, terminated by exactly one CRLF,
+    /// that is ready to be sent to the server. If the buffer the message was parsed from
+    /// already has a CRLF right after the message, such as a line read from the network,
+    /// the buffer is shared rather than copied.
+    pub fn to_wire_bytes(&self) -> Bytes {
+        let end = self.span.end;
+
+        if self.message[end..].starts_with("\r\n") {
+            return Bytes::from_owner(SharedBuffer(self.message.clone()))
+                .slice(self.span.start..end + 2);
+        }
+
+        let line = self.line();
+        let mut bytes = BytesMut::with_capacity(line.len() + 2);
+
+        bytes.extend_from_slice(line.as_bytes());
+        bytes.extend_from_slice(b"\r\n");
+        bytes.freeze()
+    }
+
+    /// Renders this message the same as `to_wire_bytes`, but returns an error rather than the line
+    /// if its wire length (excluding tags, including CRLF) exceeds the limit, such as
+    /// `MAX_MESSAGE_LENGTH`, or if its tags exceed `MAX_TAGS_LENGTH`.
+    pub fn encode_checked(&self, limit: usize) -> Result<Bytes, MessageParseError> {
+        self.check_length(limit)?;
+
+        Ok(self.to_wire_bytes())
+    }
+
+    /// Writes this message as a complete IRC line, terminated by exactly one CRLF,
+    /// to the given writer.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.line().as_bytes())?;
+        writer.write_all(b"\r\n")
+    }
+
+    /// The raw message without any line terminator it may have been constructed with.
+    fn line(&self) -> &str {
+        self.raw_message().trim_end_matches(['\r', '\n'])
+    }
+
+    /// Verifies that the wire length in bytes of the message (excluding tags, including CRLF)
+    /// does not exceed the limit, and that the tags do not exceed `MAX_TAGS_LENGTH`.
+    fn check_length(&self, limit: usize) -> Result<(), MessageParseError> {
+        let (tags_len, len) = self.wire_lengths();
+
+        if tags_len > MAX_TAGS_LENGTH {
+            return Err(MessageParseError::TagsTooLong { len: tags_len });
+        }
+
+        if len > limit {
+            return Err(MessageParseError::MessageTooLong { len });
+        }
+
+        Ok(())
+    }
+
+    /// The length in bytes of the tags of the message and of the rest of the message including CRLF.
+    fn wire_lengths(&self) -> (usize, usize) {
+        let line = self.line();
+        let tags_len = if line.starts_with('@') {
+            line.find(' ').map_or(line.len(), |position| position + 1)
+        } else {
+            0
+        };
+
+        (tags_len, line.len() - tags_len + 2)
+    }
+
+    /// A heuristic for flagging messages that may have been truncated by the server. A message
+    /// is considered likely truncated if its wire length (excluding tags, including CRLF) has
+    /// reached the limit and its final argument does not end on a natural boundary such as
+    /// whitespace or sentence punctuation. This can produce both false positives and negatives.
+    pub fn likely_truncated(&self, limit: usize) -> bool {
+        let (_, len) = self.wire_lengths();
+        if len < limit {
+            return false;
+        }
+
+        match self
+            .raw_args()
+            .next_back()
+            .and_then(|arg| arg.chars().last())
+        {
+            Some(last) => !(last.is_whitespace() || matches!(last, '.' | '!' | '?')),
+            None => true,
+        }
+    }
+
+    /// Creates a copy of this message that is safe to log, with any secrets replaced by `***`.
+    /// This covers the password of PASS, OPER and REGISTER commands and the payload of AUTHENTICATE commands.
+    /// Messages without secrets are copied unchanged.
+    pub fn redacted(&self) -> Message {
+        let skip = match self.raw_command() {
+            "PASS" | "AUTHENTICATE" => 0,
+            "OPER" => 1,
+            // NOTE: Responses from the server share the command but carry no secrets.
+            "REGISTER"
+                if !matches!(
+                    self.raw_args().next(),
+                    Some("SUCCESS" | "VERIFICATION_REQUIRED")
+                ) =>
+            {
+                2
+            }
+            _ => return self.clone(),
+        };
+
+        let ranges = self.argument_ranges();
+        let mut redacted = self.raw_message().to_owned();
+
+        for range in ranges.iter().skip(skip).rev() {
+            redacted.replace_range(range.clone(), "***");
+        }
+
+        // NOTE: Only argument values are replaced, so the message still parses the same way.
+        Message::try_from(redacted).unwrap_or_else(|_| self.clone())
+    }
+
+    /// Determines whether this message concerns the given nick under the casemapping, such as the
+    /// client's own nick. The subject depends on the command: the kicked nick of a KICK, the source
+    /// of a JOIN, PART, QUIT or NICK, the target of an INVITE, MODE, PRIVMSG, NOTICE or TAGMSG, and
+    /// the client nick of a numeric. Other commands never concern a nick.
+    pub fn concerns_nick(&self, nick: &str, mapping: CaseMapping) -> bool {
+        let subject = match self.raw_command() {
+            "KICK" => self.raw_args().nth(1),
+            "JOIN" | "PART" | "QUIT" | "NICK" => self.prefix().map(|(source, _, _)| source),
+            "INVITE" | "MODE" | "PRIVMSG" | "NOTICE" | "TAGMSG" => self.raw_args().next(),
+            command if command::is_numeric(command) => self.raw_args().next(),
+            _ => None,
+        };
+
+        subject.is_some_and(|subject| mapping.eq_ignore_case(subject, nick))
+    }
+
+    /// Renders this message as a human-friendly line for a log or console view, with any formatting
+    /// stripped. A PRIVMSG renders as `<nick> text`, a CTCP ACTION as `* nick text`, and a NOTICE as
+    /// `-nick- text`. Other CTCP requests and replies render as `* nick requested VERSION` and
+    /// `* nick replied VERSION payload`, without their `\x01` delimiters. Any other message renders as its source followed by its command and arguments.
+    pub fn display_line(&self) -> String {
+        let source = self.prefix().map_or("", |(name, _, _)| name);
+        let text = self.raw_args().nth(1).unwrap_or_default();
+
+        let line = match (self.raw_command(), self.ctcp_request()) {
+            (_, Some(("ACTION", action))) => format!("* {} {}", source, action.unwrap_or_default()),
+            (_, Some(request)) => format!("* {} requested {}", source, Self::ctcp_text(request)),
+            ("PRIVMSG", None) => format!("<{}> {}", source, text),
+            ("NOTICE", _) => match self.ctcp_reply() {
+                Some(reply) => format!("* {} replied {}", source, Self::ctcp_text(reply)),
+                None => format!("-{}- {}", source, text),
+            },
+            (command, _) => {
+                let mut parts = vec![source, command];
+                parts.extend(self.raw_args());
+                parts.retain(|part| !part.is_empty());
+                parts.join(" ")
+            }
+        };
+
+        format::strip(&line)
+    }
+
+    fn ctcp_text((command, payload): (&str, Option<&str>)) -> String {
+        match payload {
+            Some(payload) => format!("{} {}", command, payload),
+            None => command.to_owned(),
+        }
+    }
+
+    /// Attempt to get the CTCP command and payload of a CTCP request, which is a PRIVMSG whose body
+    /// is wrapped in `\x01`, such as `VERSION`. Clients are expected to reply to requests.
+    pub fn ctcp_request(&self) -> Option<(&str, Option<&str>)> {
+        self.ctcp("PRIVMSG")
+    }
+
+    /// Attempt to get the CTCP command and payload of a CTCP reply, which is a NOTICE whose body
+    /// is wrapped in `\x01`. Clients must never reply to a reply.
+    pub fn ctcp_reply(&self) -> Option<(&str, Option<&str>)> {
+        self.ctcp("NOTICE")
+    }
+
+    fn ctcp(&self, command: &str) -> Option<(&str, Option<&str>)> {
+        if self.raw_command() != command {
+            return None;
+        }
+
+        let Ctcp(ctcp_command, payload) = Ctcp::parse(self.raw_args().nth(1)?)?;
+
+        Some((ctcp_command, payload))
+    }
+
+    /// Get the line number, starting from 1, of this message within the buffer it was parsed from.
+    /// This is only set for messages parsed by `parse_lines`, otherwise it returns `None`.
+    pub fn line_number(&self) -> Option<usize> {
+        self.source.map(|(line_number, _)| line_number)
+    }
+
+    /// Get the byte offset of the start of this message within the buffer it was parsed from.
+    /// This is only set for messages parsed by `parse_lines`, otherwise it returns `None`.
+    pub fn source_offset(&self) -> Option<usize> {
+        self.source.map(|(_, offset)| offset)
+    }
+
+    /// Parses the tags, prefix and command of a message, but defers parsing its arguments until they
+    /// are first accessed. This avoids the work of parsing the arguments of messages that are only
+    /// routed or ignored based on their command. See `MessageHeader` for details.
+    pub fn parse_header_only(
+        value: impl Into<Arc<str>>,
+    ) -> Result<MessageHeader, MessageParseError> {
+        parser::parse_header_only(value)
     }
 
     pub fn try_from(
@@ -123,6 +524,179 @@ impl Message {
     ) -> MesssageParseResult {
         value.try_into()
     }
+
+    /// Constructs a message from a single line of user-provided input, which may end with one CRLF.
+    /// Any other CR or LF would split the input into multiple messages on the wire, so it is rejected
+    /// with `UnexpectedLineBreak` rather than being parsed as part of one message.
+    pub fn try_from_line(line: &str) -> MesssageParseResult {
+        let content = line.strip_suffix("\r\n").unwrap_or(line);
+
+        if let Some(position) = content.bytes().position(|c| matches!(c, b'\r' | b'\n')) {
+            return Err(MessageParseError::UnexpectedLineBreak { position });
+        }
+
+        Message::try_from(content)
+    }
+
+    /// Constructs a message the same as `try_from`, but rejects messages whose length on the wire
+    /// exceeds `MAX_MESSAGE_LENGTH` bytes (excluding tags) or whose tags exceed `MAX_TAGS_LENGTH` bytes.
+    pub fn try_from_checked(
+        value: impl std::convert::TryInto<Message, Error = MessageParseError>,
+    ) -> MesssageParseResult {
+        let message = value.try_into()?;
+        message.check_length(MAX_MESSAGE_LENGTH)?;
+
+        Ok(message)
+    }
+}
+
+/// Parses a message the same as `Message::try_from`, but collects the ranges of its tags and arguments
+/// into the given buffers, which are cleared beforehand. The parsed message borrows the input and the
+/// buffers, so reusing the same buffers across many parses avoids allocating for each message.
+pub fn parse_into<'a>(
+    message: &'a str,
+    buffers: &'a mut ParseBuffers,
+) -> Result<ParsedRef<'a>, MessageParseError> {
+    parser::parse_ref(message, buffers)
+}
+
+/// Parses each line of a buffer, such as the contents of a log file, into a message annotated with
+/// its line number and byte offset within the buffer. Lines may end with either LF or CRLF, and
+/// empty lines are skipped, though they are still counted towards the line numbers.
+pub fn parse_lines(buffer: &str) -> impl Iterator<Item = MesssageParseResult> + '_ {
+    let mut buffers = ParseBuffers::new();
+    let mut offset = 0;
+
+    buffer
+        .split_inclusive('\n')
+        .enumerate()
+        .filter_map(move |(index, line)| {
+            let start = offset;
+            offset += line.len();
+
+            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+            if line.is_empty() {
+                return None;
+            }
+
+            Some(
+                parser::parse_message_into(line, &mut buffers).map(|mut message| {
+                    message.source = Some((index + 1, start));
+                    message
+                }),
+            )
+        })
+}
+
+/// Parses every complete line of the input into a message, returning the messages along with any
+/// trailing partial line that has yet to be terminated, such as the remainder of a TCP read. Lines may
+/// end with either LF or CRLF and empty lines are skipped. The parsed messages share a single copy of
+/// the complete lines rather than each holding a copy of its own line.
+pub fn parse_many(input: &str) -> (Vec<MesssageParseResult>, &str) {
+    let end = input.rfind('\n').map_or(0, |position| position + 1);
+    let (complete, remainder) = input.split_at(end);
+
+    let shared: Arc<str> = Arc::from(complete);
+    let mut buffers = ParseBuffers::new();
+    let mut messages = Vec::new();
+    let mut start = 0;
+
+    for line in complete.split_inclusive('\n') {
+        let span = start..start + line.trim_end_matches('\n').trim_end_matches('\r').len();
+        start += line.len();
+
+        if !span.is_empty() {
+            messages.push(parser::parse_span_into(shared.clone(), span, &mut buffers));
+        }
+    }
+
+    (messages, remainder)
+}
+
+/// Normalizes a list of message targets by mapping each to its lowercase form under the
+/// given casemapping and removing duplicates, preserving the order in which targets were first seen.
+/// Empty targets are discarded.
+pub fn normalize_targets(targets: &[&str], mapping: CaseMapping) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(targets.len());
+
+    for target in targets.iter().map(|target| target.trim()) {
+        let target = mapping.to_lowercase(target);
+
+        if !target.is_empty() && !normalized.contains(&target) {
+            normalized.push(target);
+        }
+    }
+
+    normalized
+}
+
+impl PartialEq for Message {
+    /// Compares the command, prefix, arguments and tags of two messages, where the tags are
+    /// compared the same as `tags_equal`, ignoring their order.
+    fn eq(&self, other: &Message) -> bool {
+        self.raw_command() == other.raw_command()
+            && self.prefix() == other.prefix()
+            && self.raw_args().eq(other.raw_args())
+            && self.tags_equal(other)
+    }
+}
+
+impl Eq for Message {}
+
+impl Hash for Message {
+    /// Hashes the same content compared by `PartialEq`, with the tags hashed in order of their keys.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw_command().hash(state);
+        self.prefix().hash(state);
+
+        for argument in self.raw_args() {
+            argument.hash(state);
+        }
+
+        let mut tags: Vec<_> = self.tag_map().into_iter().collect();
+        tags.sort_unstable();
+        tags.hash(state);
+    }
+}
+
+impl fmt::Debug for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Message")
+            .field("tags", &self.raw_tags().collect::<Vec<_>>())
+            .field("prefix", &self.prefix())
+            .field("command", &self.raw_command())
+            .field("arguments", &self.raw_args().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl fmt::Display for Prefix<'_> {
+    /// Writes the prefix exactly as it appears on the wire, without the leading `:`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Prefix::Server(name) => f.write_str(name),
+            Prefix::User { nick, user, host } => {
+                f.write_str(nick)?;
+
+                if let Some(user) = user {
+                    write!(f, "!{}", user)?;
+                }
+
+                if let Some(host) = host {
+                    write!(f, "@{}", host)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Message {
+    /// Writes the message exactly as it appears on the wire, without the trailing CRLF.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.raw_message())
+    }
 }
 
 use std::convert::TryFrom;
@@ -150,3 +724,766 @@ impl<'a> TryFrom<&'a str> for Message {
         parser::parse_message(value)
     }
 }
+
+impl FromStr for Message {
+    type Err = MessageParseError;
+
+    fn from_str(value: &str) -> MesssageParseResult {
+        parser::parse_message(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_concerns_nick_for_kick() -> Result<()> {
+        let kick = Message::try_from(":overlord!o@memes.com KICK #memes Robot[1] :no bots")?;
+        let other = Message::try_from(":overlord!o@memes.com KICK #memes spammer :no spam")?;
+
+        assert!(kick.concerns_nick("robot{1}", CaseMapping::Rfc1459));
+        assert!(!kick.concerns_nick("robot{1}", CaseMapping::Ascii));
+        assert!(!other.concerns_nick("robot{1}", CaseMapping::Rfc1459));
+        Ok(())
+    }
+
+    #[test]
+    fn test_concerns_nick_for_nick_change() -> Result<()> {
+        let own = Message::try_from(":Robot!r@memes.com NICK robot_")?;
+        let other = Message::try_from(":overlord!o@memes.com NICK robot")?;
+
+        assert!(own.concerns_nick("robot", CaseMapping::Rfc1459));
+        assert!(!other.concerns_nick("robot", CaseMapping::Rfc1459));
+        Ok(())
+    }
+
+    #[test]
+    fn test_concerns_nick_for_unrelated_message() -> Result<()> {
+        let privmsg = Message::try_from(":overlord!o@memes.com PRIVMSG #memes :robot is cool")?;
+        let ping = Message::try_from("PING robot")?;
+
+        assert!(!privmsg.concerns_nick("robot", CaseMapping::Rfc1459));
+        assert!(!ping.concerns_nick("robot", CaseMapping::Rfc1459));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_many_with_partial_line() -> Result<()> {
+        let (messages, remainder) =
+            parse_many("PING :a\r\n:robot PRIVMSG #memes :memes\n\r\nPONG :b");
+        let messages = messages
+            .into_iter()
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        assert_eq!(2, messages.len());
+        assert_eq!("PING :a", messages[0].raw_message());
+        assert_eq!(vec!["a"], messages[0].raw_args().collect::<Vec<_>>());
+        assert_eq!(":robot PRIVMSG #memes :memes", messages[1].raw_message());
+        assert_eq!(Some(("robot", None, None)), messages[1].prefix());
+        assert_eq!("PRIVMSG", messages[1].raw_command());
+        assert_eq!(
+            vec!["#memes", "memes"],
+            messages[1].raw_args().collect::<Vec<_>>()
+        );
+        assert!(Arc::ptr_eq(&messages[0].message, &messages[1].message));
+        assert_eq!("PONG :b", remainder);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_many_without_complete_line() {
+        let (messages, remainder) = parse_many("PING :a");
+
+        assert!(messages.is_empty());
+        assert_eq!("PING :a", remainder);
+    }
+
+    #[test]
+    fn test_hash_consistent_with_eq() -> Result<()> {
+        let mut messages = std::collections::HashSet::new();
+        messages.insert(Message::try_from("@a=1;b=2 :robot PRIVMSG #memes :memes")?);
+        messages.insert(Message::try_from("@b=2;a=1 :robot PRIVMSG #memes memes")?);
+
+        assert_eq!(1, messages.len());
+
+        messages.insert(Message::try_from("@b=2;a=3 :robot PRIVMSG #memes memes")?);
+
+        assert_eq!(2, messages.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_eq_ignores_tag_order() -> Result<()> {
+        let first = Message::try_from("@a=1;b=2 :robot PRIVMSG #memes :memes for all")?;
+        let second = Message::try_from("@b=2;a=3 :robot PRIVMSG #memes :memes for all")?;
+        let third = Message::try_from("@b=2;a=1 :robot PRIVMSG #memes :memes for all")?;
+
+        assert_eq!(first, third);
+        assert_ne!(first, second);
+        Ok(())
+    }
+
+    #[test]
+    fn test_eq_compares_content() -> Result<()> {
+        let message = Message::try_from("@a=1 :robot PRIVMSG #memes :memes")?;
+
+        assert_eq!(
+            message,
+            Message::try_from("@a=1 :robot PRIVMSG #memes memes")?
+        );
+        assert_ne!(
+            message,
+            Message::try_from("@a=2 :robot PRIVMSG #memes :memes")?
+        );
+        assert_ne!(
+            message,
+            Message::try_from("@a=1 :other PRIVMSG #memes :memes")?
+        );
+        assert_ne!(
+            message,
+            Message::try_from("@a=1 :robot NOTICE #memes :memes")?
+        );
+        assert_ne!(
+            message,
+            Message::try_from("@a=1 :robot PRIVMSG #memes :dreams")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_redacted_pass() -> Result<()> {
+        let message = Message::try_from("@a=b :robot PASS hunter2")?;
+        let redacted = message.redacted();
+
+        assert_eq!("@a=b :robot PASS ***", redacted.raw_message());
+        assert_eq!(Some(("robot", None, None)), redacted.prefix());
+        assert_eq!(Some(Some("b")), redacted.get_tag("a"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_redacted_oper_and_authenticate() -> Result<()> {
+        let oper = Message::try_from("OPER overlord :hunter 2")?;
+        let authenticate = Message::try_from("AUTHENTICATE cm9ib3QAcm9ib3QAaHVudGVyMg==")?;
+        let privmsg = Message::try_from("PRIVMSG #memes :hunter2")?;
+
+        assert_eq!("OPER overlord :***", oper.redacted().raw_message());
+        assert_eq!("AUTHENTICATE ***", authenticate.redacted().raw_message());
+        assert_eq!(
+            "REGISTER robot * ***",
+            Message::try_from("REGISTER robot * hunter2")?
+                .redacted()
+                .raw_message()
+        );
+        assert_eq!(
+            "REGISTER SUCCESS robot :Account created",
+            Message::try_from("REGISTER SUCCESS robot :Account created")?
+                .redacted()
+                .raw_message()
+        );
+        assert_eq!("PRIVMSG #memes :hunter2", privmsg.redacted().raw_message());
+        Ok(())
+    }
+
+    #[test]
+    fn test_debug() -> Result<()> {
+        let message = Message::try_from("@a=b :robot PRIVMSG #memes :memes for all")?;
+        let debug = format!("{:?}", message);
+
+        assert!(debug.contains("\"PRIVMSG\""));
+        assert!(debug.contains("\"memes for all\""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prefix_display_round_trip() -> Result<()> {
+        for prefix in [
+            "foo!bar@baz.com",
+            "foo!bar",
+            "foo@baz.com",
+            "foo",
+            "irc.memes.com",
+        ] {
+            let message = Message::try_from(format!(":{} PING", prefix))?;
+
+            assert_eq!(prefix, message.prefix_parsed().unwrap().to_string());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_checked_within_limit() -> Result<()> {
+        let message = Message::try_from("PRIVMSG #memes :memes for all")?;
+
+        assert_eq!(
+            &b"PRIVMSG #memes :memes for all\r\n"[..],
+            message.encode_checked(MAX_MESSAGE_LENGTH)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_checked_over_limit() -> Result<()> {
+        let message = Message::try_from(format!("PRIVMSG #memes :{}", "a".repeat(500)))?;
+
+        assert!(matches!(
+            message.encode_checked(MAX_MESSAGE_LENGTH),
+            Err(MessageParseError::MessageTooLong { len: 518 })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ctcp_request() -> Result<()> {
+        let version = Message::try_from(":robot!r@memes.com PRIVMSG overlord :\x01VERSION\x01")?;
+        let action = Message::try_from("PRIVMSG #memes :\x01ACTION dances\x01")?;
+
+        assert_eq!(Some(("VERSION", None)), version.ctcp_request());
+        assert_eq!(None, version.ctcp_reply());
+        assert_eq!(Some(("ACTION", Some("dances"))), action.ctcp_request());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ctcp_reply() -> Result<()> {
+        let version =
+            Message::try_from(":overlord!o@memes.com NOTICE robot :\x01VERSION memebot 1.0\x01")?;
+        let plain = Message::try_from("NOTICE robot :VERSION memebot 1.0")?;
+
+        assert_eq!(Some(("VERSION", Some("memebot 1.0"))), version.ctcp_reply());
+        assert_eq!(None, version.ctcp_request());
+        assert_eq!(None, plain.ctcp_reply());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_lines_records_offsets() -> Result<()> {
+        let buffer = "PING :a\r\n:robot PRIVMSG #memes :memes\n\nPONG :b";
+        let messages = parse_lines(buffer).collect::<std::result::Result<Vec<_>, _>>()?;
+
+        assert_eq!(3, messages.len());
+        assert_eq!(Some(1), messages[0].line_number());
+        assert_eq!(Some(0), messages[0].source_offset());
+        assert_eq!(Some(2), messages[1].line_number());
+        assert_eq!(Some(9), messages[1].source_offset());
+        assert_eq!(Some(4), messages[2].line_number());
+        assert_eq!(Some(39), messages[2].source_offset());
+        assert_eq!("PONG :b", &buffer[messages[2].source_offset().unwrap()..]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_direct_parse_has_no_source() -> Result<()> {
+        let message = Message::try_from("PING :a")?;
+
+        assert_eq!(None, message.line_number());
+        assert_eq!(None, message.source_offset());
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_joined() -> Result<()> {
+        let trailing = Message::try_from(":robot TEST a b :c d")?;
+        let middle = Message::try_from("TEST a :b")?;
+        let empty = Message::try_from("TEST a :")?;
+        let none = Message::try_from("TEST")?;
+
+        assert_eq!("a b :c d", trailing.args_joined());
+        assert_eq!("a b", middle.args_joined());
+        assert_eq!("a :", empty.args_joined());
+        assert_eq!("", none.args_joined());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_tag() -> Result<()> {
+        let msg = Message::try_from("@msgid=abc;+draft/reply TAGMSG #memes")?;
+
+        assert_eq!(Some(Some("abc")), msg.get_tag("msgid"));
+        assert_eq!(Some(None), msg.get_tag("+draft/reply"));
+        assert_eq!(None, msg.get_tag("time"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_vendored_client_tags() -> Result<()> {
+        let msg = Message::try_from(
+            "@+example.com/foo=bar;example.com/baz=qux;+typing=active TAGMSG #memes",
+        )?;
+
+        assert_eq!(
+            vec![
+                ("+example.com/foo", Some("bar")),
+                ("+typing", Some("active"))
+            ],
+            msg.client_tags().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![("example.com/baz", Some("qux"))],
+            msg.server_tags().collect::<Vec<_>>()
+        );
+        assert_eq!(Some(Some("bar")), msg.get_tag("+example.com/foo"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tags_map_last_value_wins() -> Result<()> {
+        let msg = Message::try_from("@a=1;b;a=2;c=3 TAGMSG #memes")?;
+        let tags = msg.tags_map();
+
+        assert_eq!(3, tags.len());
+        assert_eq!(Some(&Some("2")), tags.get("a"));
+        assert_eq!(Some(&None), tags.get("b"));
+        assert_eq!(Some(&Some("3")), tags.get("c"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tags_equal_ignores_order_and_escapes() -> Result<()> {
+        let first = Message::try_from("@a=b\\sc;id=1;flag TAGMSG #memes")?;
+        let second = Message::try_from("@flag=;id=1;a=b\\sc TAGMSG #memes")?;
+        let third = Message::try_from("@id=1;a=b\\sc TAGMSG #memes")?;
+
+        assert!(first.tags_equal(&second));
+        assert!(!first.tags_equal(&third));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tags_equal_with_differing_value() -> Result<()> {
+        let first = Message::try_from("@a=1;b=2 PING")?;
+        let second = Message::try_from("@b=2;a=3 PING")?;
+
+        assert!(!first.tags_equal(&second));
+        Ok(())
+    }
+
+    #[test]
+    fn test_raw_args_rev() -> Result<()> {
+        let msg = Message::try_from("TEST a b :c d")?;
+        let args: Vec<_> = msg.raw_args_rev().collect();
+
+        assert_eq!(vec!["c d", "b", "a"], args);
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_line_privmsg() -> Result<()> {
+        let msg = Message::try_from(
+            ":overlord!o@memes.com PRIVMSG #memes :\x02memes\x02 are \x0304great",
+        )?;
+
+        assert_eq!("<overlord> memes are great", msg.display_line());
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_line_action() -> Result<()> {
+        let msg = Message::try_from(":overlord!o@memes.com PRIVMSG #memes :\x01ACTION dances\x01")?;
+
+        assert_eq!("* overlord dances", msg.display_line());
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_line_ctcp() -> Result<()> {
+        let request = Message::try_from(":overlord!o@memes.com PRIVMSG robots :\x01VERSION\x01")?;
+        let reply =
+            Message::try_from(":robots!r@memes.com NOTICE overlord :\x01VERSION memebot 1.0\x01")?;
+        let ping = Message::try_from(":overlord!o@memes.com PRIVMSG robots :\x01PING 1234\x01")?;
+
+        assert_eq!("* overlord requested VERSION", request.display_line());
+        assert_eq!("* robots replied VERSION memebot 1.0", reply.display_line());
+        assert_eq!("* overlord requested PING 1234", ping.display_line());
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_line_notice() -> Result<()> {
+        let msg = Message::try_from(":irc.example.com NOTICE robots :Looking up your hostname")?;
+
+        assert_eq!(
+            "-irc.example.com- Looking up your hostname",
+            msg.display_line()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_line_join() -> Result<()> {
+        let msg = Message::try_from(":overlord!o@memes.com JOIN #memes")?;
+
+        assert_eq!("overlord JOIN #memes", msg.display_line());
+        assert_eq!(
+            "PING irc.example.com",
+            Message::try_from("PING :irc.example.com")?.display_line()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_raw_args_len() -> Result<()> {
+        let msg = Message::try_from("TEST a b :c d")?;
+        let mut args = msg.raw_args();
+
+        assert_eq!(3, args.len());
+        assert_eq!(args.len(), msg.raw_args().count());
+
+        args.next();
+        args.next_back();
+        assert_eq!(1, args.len());
+        assert_eq!(0, Message::try_from("TEST")?.raw_args().len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_arg() -> Result<()> {
+        let msg = Message::try_from(":irc.example.com 401 robots overlord :No such nick/channel")?;
+
+        assert_eq!(Some("robots"), msg.arg(0));
+        assert_eq!(Some("overlord"), msg.arg(1));
+        assert_eq!(Some("No such nick/channel"), msg.arg(2));
+        assert_eq!(None, msg.arg(3));
+        assert_eq!(None, Message::try_from("TEST")?.arg(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_arg_bytes() -> Result<()> {
+        let msg = Message::try_from("PRIVMSG #memes :💖 memes")?;
+
+        for index in 0..3 {
+            assert_eq!(msg.arg(index).map(str::as_bytes), msg.arg_bytes(index));
+        }
+        assert_eq!(Some("💖 memes".as_bytes()), msg.arg_bytes(1));
+        assert_eq!(None, msg.arg_bytes(2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_raw_args_get_after_advancing() -> Result<()> {
+        let msg = Message::try_from("TEST a b :c d")?;
+        let mut args = msg.raw_args();
+
+        args.next();
+        assert_eq!(Some("b"), args.get(0));
+        assert_eq!(Some("c d"), args.get(1));
+        assert_eq!(None, args.get(2));
+        assert_eq!(Some("b"), args.next());
+        Ok(())
+    }
+
+    #[test]
+    fn test_numeric_args() -> Result<()> {
+        let msg = Message::try_from(":irc.memes.com 353 robot = #memes :robot1 robot2")?;
+        let args: Vec<_> = msg.numeric_args().unwrap().collect();
+
+        assert_eq!(vec!["=", "#memes", "robot1 robot2"], args);
+        Ok(())
+    }
+
+    #[test]
+    fn test_numeric_args_without_arguments() -> Result<()> {
+        let msg = Message::try_from(":irc.memes.com 999")?;
+
+        assert_eq!(0, msg.numeric_args().unwrap().count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_numeric_for_uncommon_codes() -> Result<()> {
+        let cases = [
+            (
+                "042 robot 0PNAAAAAB :your unique ID",
+                42,
+                vec!["0PNAAAAAB", "your unique ID"],
+            ),
+            (
+                "396 robot memes/robot :is now your displayed host",
+                396,
+                vec!["memes/robot", "is now your displayed host"],
+            ),
+            (
+                "900 robot robot!r@memes.com robot :You are now logged in as robot",
+                900,
+                vec![
+                    "robot!r@memes.com",
+                    "robot",
+                    "You are now logged in as robot",
+                ],
+            ),
+            ("999 robot", 999, vec![]),
+        ];
+
+        for (line, code, args) in cases {
+            let message = Message::try_from(line)?;
+
+            assert_eq!(Some(code), message.numeric());
+            assert_eq!(args, message.numeric_args().unwrap().collect::<Vec<_>>());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_numeric_with_leading_zeros() -> Result<()> {
+        let welcome = Message::try_from(":irc.memes.com 001 robot :Welcome to memes")?;
+        let privmsg = Message::try_from("PRIVMSG #memes :001")?;
+
+        assert_eq!(Some(1), welcome.numeric());
+        assert_eq!(None, privmsg.numeric());
+        Ok(())
+    }
+
+    #[cfg(feature = "twitch-client")]
+    #[test]
+    fn test_numeric_with_leading_zeros_matches_typed_command() -> Result<()> {
+        use crate::command::Welcome;
+        use anyhow::Context;
+
+        let welcome = Message::try_from(":irc.memes.com 001 robot :Welcome to memes")?;
+        let Welcome(user, message) = welcome.command().context("Invalid welcome command.")?;
+
+        assert_eq!(Some(1), welcome.numeric());
+        assert_eq!("robot", user);
+        assert_eq!("Welcome to memes", message);
+        Ok(())
+    }
+
+    #[test]
+    fn test_numeric_args_for_non_numeric() -> Result<()> {
+        let msg = Message::try_from("PRIVMSG #memes :hello")?;
+
+        assert!(msg.numeric_args().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_network_name() -> Result<()> {
+        let msg = Message::try_from(
+            ":irc.libera.chat 005 robot CHANTYPES=# NETWORK=Libera.Chat NICKLEN=16 :are supported by this server",
+        )?;
+
+        assert_eq!(Some("Libera.Chat"), msg.network_name());
+        Ok(())
+    }
+
+    #[test]
+    fn test_network_name_missing() -> Result<()> {
+        let isupport = Message::try_from("005 robot CHANTYPES=# :are supported by this server")?;
+        let other = Message::try_from("001 robot :NETWORK=Libera.Chat")?;
+
+        assert_eq!(None, isupport.network_name());
+        assert_eq!(None, other.network_name());
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_and_server_tags() -> Result<()> {
+        let msg = Message::try_from(
+            "@+typing=active;time=2019-11-30T12:34:56.789Z;+draft/reply=123 TAGMSG #memes",
+        )?;
+
+        let client_tags: Vec<_> = msg.client_tags().collect();
+        let server_tags: Vec<_> = msg.server_tags().collect();
+
+        assert_eq!(
+            vec![("+typing", Some("active")), ("+draft/reply", Some("123"))],
+            client_tags
+        );
+        assert_eq!(
+            vec![("time", Some("2019-11-30T12:34:56.789Z"))],
+            server_tags
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_wire_bytes() -> Result<()> {
+        let msg = Message::try_from("PING :test.host.com")?;
+
+        assert_eq!(&b"PING :test.host.com\r\n"[..], msg.to_wire_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_wire_bytes_with_existing_line_terminator() -> Result<()> {
+        let crlf = Message::try_from("PING :test.host.com\r\n")?;
+        let lf = Message::try_from("PING :test.host.com\n")?;
+
+        assert_eq!(&b"PING :test.host.com\r\n"[..], crlf.to_wire_bytes());
+        assert_eq!(&b"PING :test.host.com\r\n"[..], lf.to_wire_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_wire_bytes_shares_buffer_with_crlf() -> Result<()> {
+        let crlf = Message::try_from("PING :test.host.com\r\n")?;
+        let bare = Message::try_from("PING :test.host.com")?;
+
+        assert_eq!(crlf.raw_message().as_ptr(), crlf.to_wire_bytes().as_ptr());
+        assert_ne!(bare.raw_message().as_ptr(), bare.to_wire_bytes().as_ptr());
+
+        let (messages, _) = parse_many("PING :a\r\nPING :b\r\n");
+        let second = messages[1].as_ref().map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        assert_eq!(&b"PING :b\r\n"[..], second.to_wire_bytes());
+        assert_eq!(
+            second.raw_message().as_ptr(),
+            second.to_wire_bytes().as_ptr()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_to() -> Result<()> {
+        let msg = Message::try_from("PRIVMSG #memes :hello")?;
+        let mut output = Vec::new();
+
+        msg.write_to(&mut output)?;
+
+        assert_eq!(b"PRIVMSG #memes :hello\r\n".to_vec(), output);
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_line() -> Result<()> {
+        let clean = Message::try_from_line("PRIVMSG #memes :memes are great")?;
+        let terminated = Message::try_from_line("PRIVMSG #memes :memes are great\r\n")?;
+
+        assert_eq!("PRIVMSG #memes :memes are great", clean.raw_message());
+        assert_eq!("PRIVMSG #memes :memes are great", terminated.raw_message());
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_line_with_embedded_line_break() {
+        let embedded = Message::try_from_line("PRIVMSG #memes :hi\nQUIT :bye\r\n");
+        let bare_lf = Message::try_from_line("PRIVMSG #memes :hi\n");
+        let doubled = Message::try_from_line("PRIVMSG #memes :hi\r\n\r\n");
+
+        assert!(matches!(
+            embedded,
+            Err(MessageParseError::UnexpectedLineBreak { position: 18 })
+        ));
+        assert!(matches!(
+            bare_lf,
+            Err(MessageParseError::UnexpectedLineBreak { position: 18 })
+        ));
+        assert!(matches!(
+            doubled,
+            Err(MessageParseError::UnexpectedLineBreak { position: 18 })
+        ));
+    }
+
+    #[test]
+    fn test_try_from_checked_at_limit() -> Result<()> {
+        // NOTE: 12 bytes of "PRIVMSG #a :" plus 498 bytes of text is 512 bytes with the CRLF.
+        let line = format!("PRIVMSG #a :{}", "💖".repeat(124) + "a".repeat(2).as_str());
+        let msg = Message::try_from_checked(line.as_str())?;
+
+        assert_eq!(510, msg.raw_message().len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_checked_over_limit() {
+        let line = format!("PRIVMSG #a :{}", "💖".repeat(124) + "a".repeat(3).as_str());
+        let result = Message::try_from_checked(line.as_str());
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::MessageTooLong { len: 513 })
+        ));
+    }
+
+    #[test]
+    fn test_try_from_checked_excludes_tags() -> Result<()> {
+        let line = format!("@a={} {}", "1".repeat(1000), "1".repeat(510));
+        let msg = Message::try_from_checked(line.as_str())?;
+
+        assert_eq!(510, msg.raw_command().len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_checked_tags_over_limit() {
+        let line = format!("@a={} TEST", "1".repeat(8188));
+        let result = Message::try_from_checked(line.as_str());
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::TagsTooLong { len: 8192 })
+        ));
+    }
+
+    #[test]
+    fn test_likely_truncated_at_limit() -> Result<()> {
+        let line = format!("PRIVMSG #a :{}", "a".repeat(498));
+        let msg = Message::try_from(line.as_str())?;
+
+        assert!(msg.likely_truncated(MAX_MESSAGE_LENGTH));
+        Ok(())
+    }
+
+    #[test]
+    fn test_likely_truncated_at_limit_with_natural_boundary() -> Result<()> {
+        let line = format!("PRIVMSG #a :{}.", "a".repeat(497));
+        let msg = Message::try_from(line.as_str())?;
+
+        assert!(!msg.likely_truncated(MAX_MESSAGE_LENGTH));
+        Ok(())
+    }
+
+    #[test]
+    fn test_likely_truncated_short_message() -> Result<()> {
+        let msg = Message::try_from("PRIVMSG #a :memes")?;
+
+        assert!(!msg.likely_truncated(MAX_MESSAGE_LENGTH));
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_targets() {
+        let targets = normalize_targets(
+            &["#Memes", "Robot[1]", "#memes", "robot{1}", "", "#other"],
+            CaseMapping::Rfc1459,
+        );
+
+        assert_eq!(vec!["#memes", "robot{1}", "#other"], targets);
+    }
+
+    #[test]
+    fn test_from_str() -> Result<()> {
+        let parsed: Message = "353 robots = #memes :robot1 robot2".parse()?;
+        let converted = Message::try_from("353 robots = #memes :robot1 robot2")?;
+
+        assert_eq!(converted.raw_message(), parsed.raw_message());
+        assert_eq!(converted.raw_command(), parsed.raw_command());
+        assert_eq!(
+            converted.raw_args().collect::<Vec<_>>(),
+            parsed.raw_args().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_round_trip() -> Result<()> {
+        let lines = [
+            "@badges=moderator/1;color=#FF0000;display-name=Robot;emotes= :robot!robot@robot.tmi.twitch.tv PRIVMSG #memes :Memes for all!",
+            ":tmi.twitch.tv 353 robot = #memes :robot1 robot2 robot3",
+            "PING :tmi.twitch.tv",
+            "PING",
+        ];
+
+        for line in lines.iter() {
+            let msg = Message::try_from(*line)?;
+            assert_eq!(*line, msg.to_string());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::message::Message;
+
+use std::ops::Range;
+
+/// The tag marking a line of a multiline message that is joined to the previous line without a newline.
+pub const MULTILINE_CONCAT_TAG: &str = "draft/multiline-concat";
+
+/// Reassembles the text of a `draft/multiline` batch from the messages within it. The body of each message
+/// is joined to the previous one with a newline, unless it has the `draft/multiline-concat` tag.
+pub fn reassemble_multiline(parts: &[Message]) -> String {
+    reassemble_multiline_with_ranges(parts).0
+}
+
+/// Reassembles the text of a `draft/multiline` batch the same as `reassemble_multiline`, but also returns
+/// the byte range of each message's body within the text, so that the text can be attributed to its source
+/// messages. Messages without a body are treated as an empty line.
+pub fn reassemble_multiline_with_ranges(parts: &[Message]) -> (String, Vec<Range<usize>>) {
+    let mut text = String::new();
+    let mut ranges = Vec::with_capacity(parts.len());
+
+    for (index, part) in parts.iter().enumerate() {
+        if index > 0 && part.get_tag(MULTILINE_CONCAT_TAG).is_none() {
+            text.push('\n');
+        }
+
+        // NOTE: The body is the final argument, following the target of the PRIVMSG or NOTICE.
+        let body = part.raw_args().nth(1).unwrap_or_default();
+        let start = text.len();
+        text.push_str(body);
+        ranges.push(start..text.len());
+    }
+
+    (text, ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_reassemble_multiline_with_ranges() -> Result<()> {
+        let parts = [
+            Message::try_from("@batch=1 PRIVMSG #memes :hello")?,
+            Message::try_from("@batch=1;draft/multiline-concat PRIVMSG #memes : world")?,
+            Message::try_from("@batch=1 PRIVMSG #memes :memes for all")?,
+        ];
+        let (text, ranges) = reassemble_multiline_with_ranges(&parts);
+
+        assert_eq!("hello world\nmemes for all", text);
+        assert_eq!(vec![0..5, 5..11, 12..25], ranges);
+        assert_eq!(" world", &text[ranges[1].clone()]);
+        assert_eq!(text, reassemble_multiline(&parts));
+        Ok(())
+    }
+
+    #[test]
+    fn test_reassemble_multiline_with_empty_part() -> Result<()> {
+        let parts = [
+            Message::try_from("PRIVMSG #memes :first")?,
+            Message::try_from("PRIVMSG #memes :")?,
+            Message::try_from("PRIVMSG #memes :third")?,
+        ];
+        let (text, ranges) = reassemble_multiline_with_ranges(&parts);
+
+        assert_eq!("first\n\nthird", text);
+        assert_eq!(vec![0..5, 6..6, 7..12], ranges);
+        Ok(())
+    }
+}
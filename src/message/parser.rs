@@ -1,71 +1,303 @@
+use crate::command::{ArgumentIter, Command};
 use crate::error::{MessageParseError, MessageParseError::UnexpectedEndOfInput};
-use crate::message::{Message, PrefixRange, TagRange};
+use crate::message::{Message, MessageHeader, PrefixRange, TagRange};
+use crate::tag::{Tag, TagIter};
 
 use std::ops::Range;
 use std::sync::Arc;
 
 type ParseResult<T> = Result<(T, usize), MessageParseError>;
 
+/// Reusable buffers for the tags and arguments collected while parsing a message.
+/// Reusing the same buffers across many parses avoids repeatedly growing fresh
+/// vectors for every message.
+#[derive(Default)]
+pub struct ParseBuffers {
+    tags: Vec<TagRange>,
+    arguments: Vec<Range<usize>>,
+}
+
+impl ParseBuffers {
+    pub fn new() -> ParseBuffers {
+        ParseBuffers::default()
+    }
+}
+
 pub fn parse_message(message: impl Into<Arc<str>>) -> Result<Message, MessageParseError> {
+    parse_message_into(message, &mut ParseBuffers::new())
+}
+
+pub fn parse_message_into(
+    message: impl Into<Arc<str>>,
+    buffers: &mut ParseBuffers,
+) -> Result<Message, MessageParseError> {
     let message = message.into();
+    let span = 0..message.len();
 
-    let (tags, prefix, command, arguments) = {
-        let input = message.as_bytes();
-        let (tags, position) = parse_tags(input)?;
-        let (prefix, position) = parse_prefix(input, position)?;
-        let (command, position) = parse_command(input, position)?;
-        let (args, _) = parse_args(input, position)?;
+    parse_span_into(message, span, buffers)
+}
+
+/// Parses the message found within the given span of a shared buffer. The ranges of the parsed
+/// components are relative to the start of the span, so the buffer may hold several messages.
+pub fn parse_span_into(
+    message: Arc<str>,
+    span: Range<usize>,
+    buffers: &mut ParseBuffers,
+) -> Result<Message, MessageParseError> {
+    let (mut message, arguments_start) = parse_header(message, span, buffers)?;
+
+    parse_args(
+        message.raw_message().as_bytes(),
+        arguments_start,
+        &mut buffers.arguments,
+    );
+    message.arguments = collect_arguments(&buffers.arguments);
+
+    Ok(message)
+}
 
-        (tags, prefix, command, args)
+/// Parses the tags, prefix and command of a message, leaving the arguments to be parsed on first access.
+pub fn parse_header_only(message: impl Into<Arc<str>>) -> Result<MessageHeader, MessageParseError> {
+    let message = message.into();
+    let span = 0..message.len();
+    let (message, arguments_start) = parse_header(message, span, &mut ParseBuffers::new())?;
+
+    Ok(MessageHeader::new(message, arguments_start))
+}
+
+/// Parses everything but the arguments of a message, returning the message without arguments
+/// and the position its arguments start at.
+fn parse_header(
+    message: Arc<str>,
+    span: Range<usize>,
+    buffers: &mut ParseBuffers,
+) -> Result<(Message, usize), MessageParseError> {
+    let header = parse_header_ranges(&message, span, buffers)?;
+
+    let tags = if buffers.tags.is_empty() {
+        None
+    } else {
+        Some(buffers.tags.as_slice().into())
     };
 
-    Ok(Message {
+    let message = Message {
         message,
+        span: header.span,
         tags,
+        prefix: header.prefix,
+        command: header.command,
+        arguments: None,
+        source: None,
+    };
+
+    Ok((message, header.arguments_start))
+}
+
+/// The ranges of the components of a message found by `parse_header_ranges`, relative to the
+/// start of its span. The tags are left in the buffers.
+struct HeaderRanges {
+    span: Range<usize>,
+    prefix: Option<PrefixRange>,
+    command: Range<usize>,
+    arguments_start: usize,
+}
+
+fn parse_header_ranges(
+    message: &str,
+    span: Range<usize>,
+    buffers: &mut ParseBuffers,
+) -> Result<HeaderRanges, MessageParseError> {
+    buffers.tags.clear();
+    buffers.arguments.clear();
+
+    // NOTE: Many transports hand over lines with the delimiter still attached, so a single
+    // trailing CR, LF or CRLF is stripped rather than ending up in the final argument.
+    let line = &message[span.clone()];
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let span = span.start..span.start + line.len();
+
+    if let Some(position) = line
+        .bytes()
+        .position(|c| matches!(c, b'\0' | b'\r' | b'\n'))
+    {
+        return Err(MessageParseError::IllegalCharacter {
+            byte: line.as_bytes()[position],
+            position,
+        });
+    }
+
+    let input = line.as_bytes();
+    let position = parse_tags(input, &mut buffers.tags)?;
+    let (prefix, position) = parse_prefix(input, position)?;
+    let (command, arguments_start) = parse_command(input, position)?;
+
+    Ok(HeaderRanges {
+        span,
         prefix,
         command,
-        arguments,
+        arguments_start,
+    })
+}
+
+/// Parses a message into the given buffers, borrowing both the input and the buffers rather than
+/// allocating, for messages that are inspected once and then discarded.
+pub fn parse_ref<'a>(
+    message: &'a str,
+    buffers: &'a mut ParseBuffers,
+) -> Result<ParsedRef<'a>, MessageParseError> {
+    let header = parse_header_ranges(message, 0..message.len(), buffers)?;
+    let message = &message[header.span];
+
+    parse_args(
+        message.as_bytes(),
+        header.arguments_start,
+        &mut buffers.arguments,
+    );
+
+    Ok(ParsedRef {
+        message,
+        tags: &buffers.tags,
+        prefix: header.prefix,
+        command: header.command,
+        arguments: &buffers.arguments,
     })
 }
 
+/// A message parsed by `parse_into`, which borrows the input it was parsed from and the buffers
+/// holding the ranges of its tags and arguments. As the buffers are reused by the next parse, the
+/// message must be converted with `to_message` to be kept any longer.
+pub struct ParsedRef<'a> {
+    message: &'a str,
+    tags: &'a [TagRange],
+    prefix: Option<PrefixRange>,
+    command: Range<usize>,
+    arguments: &'a [Range<usize>],
+}
+
+impl<'a> ParsedRef<'a> {
+    /// A strongly typed interface for determining the type of the command
+    /// and retrieving the values of the command.
+    pub fn command<T>(&self) -> Option<T>
+    where
+        T: Command<Output<'a> = T>,
+    {
+        <T as Command>::try_match(self.raw_command(), self.raw_args())
+    }
+
+    /// A strongly type way of accessing a specified tag associated with a message.
+    pub fn tag<T>(&self) -> Option<T>
+    where
+        T: Tag<'a>,
+    {
+        <T as Tag>::try_match(self.raw_tags())
+    }
+
+    /// Get the raw IRC command this message was constructed from.
+    pub fn raw_message(&self) -> &'a str {
+        self.message
+    }
+
+    /// Get the raw command associated with this message.
+    pub fn raw_command(&self) -> &'a str {
+        &self.message[self.command.clone()]
+    }
+
+    /// Get the name, user and host of the prefix of this message, if there is one.
+    pub fn prefix(&self) -> Option<(&'a str, Option<&'a str>, Option<&'a str>)> {
+        let prefix = self.prefix.as_ref()?;
+        let user = prefix.user.clone().map(|user| &self.message[user]);
+        let host = prefix.host.clone().map(|host| &self.message[host]);
+
+        Some((&self.message[prefix.prefix.clone()], user, host))
+    }
+
+    /// Get an iterator to the raw key/value pairs of tags associated with this message.
+    pub fn raw_tags(&self) -> TagIter<'a> {
+        TagIter::new(self.message, self.tags.iter())
+    }
+
+    /// Get an iterator to the raw arguments associated with this message.
+    pub fn raw_args(&self) -> ArgumentIter<'a> {
+        ArgumentIter::new(self.message, self.arguments.iter())
+    }
+
+    /// Copies this into a regular `Message` that owns its input and ranges.
+    pub fn to_message(&self) -> Message {
+        let tags = if self.tags.is_empty() {
+            None
+        } else {
+            Some(self.tags.into())
+        };
+
+        Message {
+            message: self.message.into(),
+            span: 0..self.message.len(),
+            tags,
+            prefix: self.prefix.clone(),
+            command: self.command.clone(),
+            arguments: collect_arguments(self.arguments),
+            source: None,
+        }
+    }
+}
+
+/// Parses the arguments of a message starting at the given position, for messages whose arguments
+/// were not parsed up front.
+pub fn parse_arguments(input: &str, position: usize) -> Option<Arc<[Range<usize>]>> {
+    let mut arguments = Vec::new();
+    parse_args(input.as_bytes(), position, &mut arguments);
+
+    collect_arguments(&arguments)
+}
+
+fn collect_arguments(arguments: &[Range<usize>]) -> Option<Arc<[Range<usize>]>> {
+    if arguments.is_empty() {
+        None
+    } else {
+        Some(arguments.into())
+    }
+}
+
 fn move_next(value: usize, bound: usize) -> Result<usize, MessageParseError> {
     let value = value + 1;
 
     if value >= bound {
-        Err(UnexpectedEndOfInput {})
+        Err(UnexpectedEndOfInput { position: value })
     } else {
         Ok(value)
     }
 }
 
-fn parse_tags(input: &[u8]) -> ParseResult<Option<Arc<[TagRange]>>> {
+fn parse_tags(input: &[u8], tags: &mut Vec<TagRange>) -> Result<usize, MessageParseError> {
     if input.is_empty() {
-        return Err(UnexpectedEndOfInput {});
+        return Err(UnexpectedEndOfInput { position: 0 });
     }
 
     if input[0] == b'@' {
         let len = input.len();
-        let mut position = move_next(0, len)?; // Skip the @
-        let mut tags: Vec<TagRange> = Vec::new();
+        // NOTE: The tags must be followed by a space and a command, so running out of input
+        // anywhere within them means the command is missing.
+        let next = |position| {
+            move_next(position, len)
+                .map_err(|_| MessageParseError::MissingCommand { position: len })
+        };
+        let mut position = next(0)?; // Skip the @
 
         loop {
             let key_start = position;
-            while input[position] != b'=' && input[position] != b';' {
-                if input[position] == b' ' {
-                    return Err(UnexpectedEndOfInput {});
-                }
-
-                position = move_next(position, len)?;
+            while input[position] != b'=' && input[position] != b';' && input[position] != b' ' {
+                position = next(position)?;
             }
 
             let key_range = key_start..position;
             if input[position] == b'=' {
-                position = move_next(position, len)?;
+                position = next(position)?;
             }
 
             let value_start = position;
             while input[position] != b';' && input[position] != b' ' {
-                position = move_next(position, len)?;
+                position = next(position)?;
             }
 
             let value_range = if value_start == position {
@@ -74,20 +306,23 @@ fn parse_tags(input: &[u8]) -> ParseResult<Option<Arc<[TagRange]>>> {
                 Some(value_start..position)
             };
 
-            tags.push((key_range, value_range));
+            // NOTE: Empty keys (such as in `@;a=1` or a bare `@ `) carry no information,
+            // so they are skipped rather than treated as malformed.
+            if !key_range.is_empty() {
+                tags.push((key_range, value_range));
+            }
 
             if input[position] == b' ' {
-                position = move_next(position, len)?;
+                position = next(position)?;
                 break;
             }
 
-            position = move_next(position, len)?;
+            position = next(position)?;
         }
 
-        let slice = tags.into();
-        Ok((Some(slice), position))
+        Ok(position)
     } else {
-        Ok((None, 0))
+        Ok(0)
     }
 }
 
@@ -95,7 +330,7 @@ fn parse_prefix(input: &[u8], mut position: usize) -> ParseResult<Option<PrefixR
     let len = input.len();
 
     if position >= len {
-        return Err(UnexpectedEndOfInput);
+        return Err(UnexpectedEndOfInput { position });
     }
 
     if input[position] == b':' {
@@ -150,7 +385,7 @@ fn parse_prefix(input: &[u8], mut position: usize) -> ParseResult<Option<PrefixR
 fn parse_command(input: &[u8], mut position: usize) -> ParseResult<Range<usize>> {
     let len = input.len();
     if position >= len {
-        return Err(UnexpectedEndOfInput);
+        return Err(UnexpectedEndOfInput { position });
     }
 
     if input[0] == b' ' {
@@ -172,14 +407,13 @@ fn parse_command(input: &[u8], mut position: usize) -> ParseResult<Range<usize>>
     Ok((command_range, position))
 }
 
-fn parse_args(input: &[u8], mut position: usize) -> ParseResult<Option<Arc<[Range<usize>]>>> {
+fn parse_args(input: &[u8], mut position: usize, args: &mut Vec<Range<usize>>) {
     let len = input.len();
 
     if position >= len {
-        return Ok((None, position));
+        return;
     }
 
-    let mut args = Vec::new();
     let mut arg_start = position;
 
     loop {
@@ -202,14 +436,61 @@ fn parse_args(input: &[u8], mut position: usize) -> ParseResult<Option<Arc<[Rang
             break;
         }
     }
-
-    let slice = args.into();
-    Ok((Some(slice), position))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::Prefix;
+
+    #[test]
+    fn parse_rejects_embedded_nul() {
+        let result = parse_message("PRIVMSG #memes :me\0mes");
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::IllegalCharacter {
+                byte: 0,
+                position: 18
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_embedded_line_feed() {
+        let result = parse_message("PRIVMSG #memes :memes\nQUIT\r\n");
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::IllegalCharacter {
+                byte: b'\n',
+                position: 21
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_strips_trailing_line_ending() {
+        for message in ["PING :x\r\n", "PING :x\n", "PING :x\r", "PING x\r\n"].iter() {
+            let result = parse_message(*message).unwrap();
+
+            assert_eq!(vec!["x"], result.raw_args().collect::<Vec<_>>());
+            assert_eq!(message.trim_end(), result.raw_message());
+        }
+    }
+
+    #[test]
+    fn parse_strips_only_one_line_ending() {
+        let result = parse_message("PING :x\n\n");
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::IllegalCharacter {
+                byte: b'\n',
+                position: 7
+            })
+        ));
+    }
 
     #[test]
     fn parse_command() {
@@ -290,6 +571,131 @@ mod tests {
         assert_eq!(expected_tags, actual_tags);
     }
 
+    #[test]
+    fn parse_with_reused_buffers() {
+        let mut buffers = ParseBuffers::new();
+        let messages = [
+            "@a=1;b=2;c :foo!bar@baz PRIVMSG #memes :Memes for all!",
+            "PING",
+            "TEST a b c :d e f",
+            "@x=y TEST",
+            ":test.server.com 001 robot :Welcome",
+        ];
+
+        for _ in 0..2 {
+            for message in messages.iter() {
+                let expected = parse_message(*message).unwrap();
+                let actual = parse_ref(message, &mut buffers).unwrap();
+
+                assert_eq!(
+                    expected.raw_tags().collect::<Vec<_>>(),
+                    actual.raw_tags().collect::<Vec<_>>()
+                );
+                assert_eq!(expected.prefix(), actual.prefix());
+                assert_eq!(expected.raw_command(), actual.raw_command());
+                assert_eq!(
+                    expected.raw_args().collect::<Vec<_>>(),
+                    actual.raw_args().collect::<Vec<_>>()
+                );
+                assert_eq!(expected, actual.to_message());
+            }
+        }
+    }
+
+    #[test]
+    fn parse_ref_borrows_input() {
+        let mut buffers = ParseBuffers::new();
+        let input = "@a=1 :foo!bar@baz PRIVMSG #memes :Memes for all!\r\n";
+        let parsed = parse_ref(input, &mut buffers).unwrap();
+
+        assert_eq!(input.as_ptr(), parsed.raw_message().as_ptr());
+        assert_eq!(input.trim_end(), parsed.raw_message());
+        assert_eq!(Some(("foo", Some("bar"), Some("baz"))), parsed.prefix());
+        assert_eq!(Some("Memes for all!"), parsed.raw_args().next_back());
+
+        assert!(matches!(
+            parse_ref("@a=b", &mut buffers),
+            Err(MessageParseError::MissingCommand { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_lone_tag_marker() {
+        let result = parse_message("@");
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::MissingCommand { position: 1 })
+        ));
+    }
+
+    #[test]
+    fn parse_truncated_tags_reports_position() {
+        let result = parse_message("@a=b;c=d");
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::MissingCommand { position: 8 })
+        ));
+    }
+
+    #[test]
+    fn parse_tags_without_command() {
+        assert!(matches!(
+            parse_message("@a=1"),
+            Err(MessageParseError::MissingCommand { position: 4 })
+        ));
+        assert!(matches!(
+            parse_message("@a=1 "),
+            Err(MessageParseError::MissingCommand { position: 5 })
+        ));
+    }
+
+    #[test]
+    fn parse_truncated_prefix_reports_position() {
+        let result = parse_message("@a=b :robot!r@memes.com");
+
+        assert!(matches!(result, Err(UnexpectedEndOfInput { position: 23 })));
+    }
+
+    #[test]
+    fn parse_command_with_empty_tags() {
+        let result = parse_message("@ TEST").unwrap();
+
+        assert_eq!(0, result.raw_tags().count());
+        assert_eq!("TEST", result.raw_command());
+    }
+
+    #[test]
+    fn parse_lone_tag_separator() {
+        let result = parse_message("@;");
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::MissingCommand { position: 2 })
+        ));
+    }
+
+    #[test]
+    fn parse_command_with_empty_tag_keys() {
+        let result = parse_message("@;a=1; TEST").unwrap();
+
+        let actual_tags: Vec<_> = result.raw_tags().collect();
+
+        assert_eq!(vec![("a", Some("1"))], actual_tags);
+        assert_eq!("TEST", result.raw_command());
+    }
+
+    #[test]
+    fn parse_command_with_trailing_tag_without_value() {
+        let result = parse_message("@a=1;b TEST").unwrap();
+
+        let actual_tags: Vec<_> = result.raw_tags().collect();
+
+        assert_eq!(vec![("a", Some("1")), ("b", None)], actual_tags);
+        assert_eq!("TEST", result.raw_command());
+    }
+
     #[test]
     fn parse_command_with_multibyte_character_arguments() {
         let result = parse_message("TEST :💖 Love 💖 Memes 💖").unwrap();
@@ -371,6 +777,56 @@ mod tests {
         assert_eq!(Some(("foo", None, Some("host.test.com"))), prefix);
     }
 
+    #[test]
+    fn parse_prefix_as_server() {
+        let result = parse_message(":test.server.com TEST").unwrap();
+
+        assert_eq!(
+            Some(Prefix::Server("test.server.com")),
+            result.prefix_parsed()
+        );
+    }
+
+    #[test]
+    fn parse_prefix_as_user() {
+        let cases = [
+            (":foo TEST", "foo", None, None),
+            (":foo!foobert TEST", "foo", Some("foobert"), None),
+            (
+                ":foo!foobert@host.test.com TEST",
+                "foo",
+                Some("foobert"),
+                Some("host.test.com"),
+            ),
+            (
+                ":foo@host.test.com TEST",
+                "foo",
+                None,
+                Some("host.test.com"),
+            ),
+        ];
+
+        for (message, nick, user, host) in cases.iter() {
+            let result = parse_message(*message).unwrap();
+
+            assert_eq!(
+                Some(Prefix::User {
+                    nick,
+                    user: *user,
+                    host: *host
+                }),
+                result.prefix_parsed()
+            );
+        }
+    }
+
+    #[test]
+    fn parse_prefix_missing() {
+        let result = parse_message("TEST").unwrap();
+
+        assert_eq!(None, result.prefix_parsed());
+    }
+
     #[test]
     fn parse_numeric_welcome() {
         let result = parse_message(
@@ -0,0 +1,197 @@
+use crate::message::{Message, MessageBuilder};
+use crate::tag;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use std::fmt;
+
+impl Serialize for Message {
+    /// Serializes the message as a structure of its tags as a map, its prefix as an object of
+    /// its name, user and host, its command, and its arguments as an array, all borrowed from the message.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Message", 4)?;
+        state.serialize_field("tags", &Tags(self))?;
+        state.serialize_field("prefix", &self.prefix().map(PrefixFields))?;
+        state.serialize_field("command", self.raw_command())?;
+        state.serialize_field("arguments", &Arguments(self))?;
+        state.end()
+    }
+}
+
+struct Tags<'a>(&'a Message);
+
+impl Serialize for Tags<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.raw_tags())
+    }
+}
+
+struct PrefixFields<'a>((&'a str, Option<&'a str>, Option<&'a str>));
+
+impl Serialize for PrefixFields<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (name, user, host) = self.0;
+
+        let mut state = serializer.serialize_struct("Prefix", 3)?;
+        state.serialize_field("name", name)?;
+        state.serialize_field("user", &user)?;
+        state.serialize_field("host", &host)?;
+        state.end()
+    }
+}
+
+struct Arguments<'a>(&'a Message);
+
+impl Serialize for Arguments<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.raw_args())
+    }
+}
+
+/// The structured form of a message, as produced by its `Serialize` implementation.
+#[derive(serde::Deserialize)]
+struct MessageFields {
+    #[serde(default)]
+    tags: TagList,
+    prefix: Option<OwnedPrefixFields>,
+    command: String,
+    #[serde(default)]
+    arguments: Vec<String>,
+}
+
+/// The tags of a message, deserialized from a map while preserving their order.
+#[derive(Default)]
+struct TagList(Vec<(String, Option<String>)>);
+
+impl<'de> Deserialize<'de> for TagList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<TagList, D::Error> {
+        struct TagListVisitor;
+
+        impl<'de> de::Visitor<'de> for TagListVisitor {
+            type Value = TagList;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a map of tags")
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<TagList, A::Error> {
+                let mut tags = Vec::new();
+                while let Some(tag) = map.next_entry()? {
+                    tags.push(tag);
+                }
+
+                Ok(TagList(tags))
+            }
+        }
+
+        deserializer.deserialize_map(TagListVisitor)
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct OwnedPrefixFields {
+    name: String,
+    user: Option<String>,
+    host: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for Message {
+    /// Deserializes the structured form of a message by rebuilding its wire format and parsing it.
+    /// Tag values are expected to be escaped, as they are when serialized.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Message, D::Error> {
+        let fields = MessageFields::deserialize(deserializer)?;
+        let mut builder = MessageBuilder::new().command(&fields.command);
+
+        for (key, value) in &fields.tags.0 {
+            builder = builder.tag(key, &tag::unescape(value.as_deref().unwrap_or_default()));
+        }
+
+        if let Some(prefix) = &fields.prefix {
+            let mut raw_prefix = prefix.name.clone();
+
+            if let Some(user) = &prefix.user {
+                raw_prefix.push('!');
+                raw_prefix.push_str(user);
+            }
+
+            if let Some(host) = &prefix.host {
+                raw_prefix.push('@');
+                raw_prefix.push_str(host);
+            }
+
+            builder = builder.prefix(raw_prefix);
+        }
+
+        for argument in &fields.arguments {
+            builder = builder.arg(argument);
+        }
+
+        builder.build().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize_tagged_privmsg() -> Result<()> {
+        let msg = Message::try_from(
+            "@msgid=abc;+draft/reply :robot!r@memes.com PRIVMSG #memes :memes for all",
+        )?;
+
+        assert_eq!(
+            json!({
+                "tags": { "msgid": "abc", "+draft/reply": null },
+                "prefix": { "name": "robot", "user": "r", "host": "memes.com" },
+                "command": "PRIVMSG",
+                "arguments": ["#memes", "memes for all"],
+            }),
+            serde_json::to_value(&msg)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_without_tags_or_prefix() -> Result<()> {
+        let msg = Message::try_from("PING :memes")?;
+
+        assert_eq!(
+            json!({
+                "tags": {},
+                "prefix": null,
+                "command": "PING",
+                "arguments": ["memes"],
+            }),
+            serde_json::to_value(&msg)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_round_trip() -> Result<()> {
+        let msg = Message::try_from(
+            "@msgid=abc;+draft/reply;a=b\\sc :robot!r@memes.com PRIVMSG #memes :memes for all",
+        )?;
+        let json = serde_json::to_string(&msg)?;
+        let deserialized: Message = serde_json::from_str(&json)?;
+
+        assert_eq!(msg.raw_message(), deserialized.raw_message());
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_rejects_empty_command() {
+        let result = serde_json::from_value::<Message>(json!({
+            "tags": {},
+            "prefix": null,
+            "command": "",
+            "arguments": ["memes"],
+        }));
+
+        assert!(result.is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::error::MessageParseError;
-use crate::message::Message;
+use crate::message::builder::needs_colon;
+use crate::message::{Message, MessageBuilder, MAX_MESSAGE_LENGTH};
 
 type Result<T> = std::result::Result<T, MessageParseError>;
 
@@ -41,7 +42,644 @@ pub fn join(channels: &str, keys: Option<&str>) -> Result<Message> {
     Message::try_from(command)
 }
 
+/// Constructs a message containing a PART command for the specified channels.
+/// The `channels` parameter is a comma separated list of channels to part.
+/// The `reason` parameter is an optional part message.
+pub fn part(channels: &str, reason: Option<&str>) -> Result<Message> {
+    let command = if let Some(reason) = reason {
+        format!("PART {} :{}", channels, reason)
+    } else {
+        format!("PART {}", channels)
+    };
+
+    Message::try_from(command)
+}
+
+/// Constructs a message containing a TOPIC command for the specified channel.
+/// If `topic` is specified, the channel's topic is set, otherwise it is queried.
+pub fn topic(channel: &str, topic: Option<&str>) -> Result<Message> {
+    let command = if let Some(topic) = topic {
+        format!("TOPIC {} :{}", channel, topic)
+    } else {
+        format!("TOPIC {}", channel)
+    };
+
+    Message::try_from(command)
+}
+
 /// Constructs a message containing a PRIVMSG command sent to the specified targets with the given message.
 pub fn priv_msg(targets: &str, message: &str) -> Result<Message> {
     Message::try_from(format!("PRIVMSG {} :{}", targets, message))
 }
+
+/// Constructs a message containing a NOTICE command sent to the specified targets with the given message.
+pub fn notice(targets: &str, message: &str) -> Result<Message> {
+    Message::try_from(format!("NOTICE {} :{}", targets, message))
+}
+
+/// Constructs a message containing a CTCP request, which is a PRIVMSG sent to the target whose body is
+/// the CTCP command and optional payload wrapped in `\x01`, such as a VERSION or PING request.
+pub fn ctcp(target: &str, command: &str, payload: Option<&str>) -> Result<Message> {
+    ctcp_message("PRIVMSG", target, command, payload)
+}
+
+/// Constructs a CTCP ACTION request, which clients typically render as an emote (`/me`).
+pub fn action(target: &str, text: &str) -> Result<Message> {
+    ctcp(target, "ACTION", Some(text))
+}
+
+/// Constructs a message containing a CTCP reply, which is a NOTICE sent to the target whose body is
+/// the CTCP command and optional payload wrapped in `\x01`, such as a reply to a VERSION request.
+pub fn ctcp_reply(target: &str, command: &str, payload: Option<&str>) -> Result<Message> {
+    ctcp_message("NOTICE", target, command, payload)
+}
+
+fn ctcp_message(
+    message_command: &str,
+    target: &str,
+    command: &str,
+    payload: Option<&str>,
+) -> Result<Message> {
+    // NOTE: A delimiter within the command or payload would end the CTCP message early.
+    for component in [Some(command), payload].into_iter().flatten() {
+        if component.contains('\x01') {
+            return Err(MessageParseError::InvalidComponent {
+                value: component.to_owned(),
+            });
+        }
+    }
+
+    if command.is_empty() || command.contains(' ') {
+        return Err(MessageParseError::InvalidComponent {
+            value: command.to_owned(),
+        });
+    }
+
+    let body = match payload {
+        Some(payload) => format!("\x01{} {}\x01", command, payload),
+        None => format!("\x01{}\x01", command),
+    };
+
+    MessageBuilder::new()
+        .command(message_command)
+        .arg(target)
+        .trailing(&body)
+        .build()
+}
+
+/// Constructs a message containing an AWAY command. If `message` is specified, the client is
+/// marked as away with that message, otherwise the client's away status is cleared.
+pub fn away(message: Option<&str>) -> Result<Message> {
+    let command = if let Some(message) = message {
+        format!("AWAY :{}", message)
+    } else {
+        "AWAY".to_owned()
+    };
+
+    Message::try_from(command)
+}
+
+/// Constructs a message containing a QUIT command with an optional reason.
+pub fn quit(reason: Option<&str>) -> Result<Message> {
+    let command = if let Some(reason) = reason {
+        format!("QUIT :{}", reason)
+    } else {
+        "QUIT".to_owned()
+    };
+
+    Message::try_from(command)
+}
+
+/// Constructs a message containing an INVITE command inviting the nick to the specified channel.
+pub fn invite(nick: &str, channel: &str) -> Result<Message> {
+    Message::try_from(format!("INVITE {} {}", nick, channel))
+}
+
+/// Constructs a message containing an ISON command, which asks the server which of the nicks are online.
+pub fn ison(nicks: &[&str]) -> Result<Message> {
+    if nicks.is_empty() {
+        return Err(MessageParseError::InvalidComponent {
+            value: String::new(),
+        });
+    }
+
+    // NOTE: The builder would allow spaces in the final nick, which would be read as more nicks.
+    if let Some(nick) = nicks
+        .iter()
+        .find(|nick| nick.is_empty() || nick.contains(' '))
+    {
+        return Err(MessageParseError::InvalidComponent {
+            value: (*nick).to_owned(),
+        });
+    }
+
+    nicks
+        .iter()
+        .fold(MessageBuilder::new().command("ISON"), |builder, nick| {
+            builder.arg(nick)
+        })
+        .build()
+}
+
+/// Constructs a message containing a WHOIS command querying the nick. If `target_server` is specified,
+/// the query is sent to that server, which is needed for accurate idle times on multi-server networks.
+pub fn whois(target_server: Option<&str>, nick: &str) -> Result<Message> {
+    let command = if let Some(target_server) = target_server {
+        format!("WHOIS {} {}", target_server, nick)
+    } else {
+        format!("WHOIS {}", nick)
+    };
+
+    Message::try_from(command)
+}
+
+/// Constructs a message containing a KICK command removing the nick from the specified channel,
+/// with an optional comment.
+pub fn kick(channel: &str, nick: &str, comment: Option<&str>) -> Result<Message> {
+    let command = if let Some(comment) = comment {
+        format!("KICK {} {} :{}", channel, nick, comment)
+    } else {
+        format!("KICK {} {}", channel, nick)
+    };
+
+    Message::try_from(command)
+}
+
+/// Constructs the pair of messages for banning and then kicking a user from a channel.
+/// The first message is a MODE command setting a ban on the specified mask, and the
+/// second is a KICK command removing the nick from the channel with the given reason.
+pub fn kickban(channel: &str, nick: &str, mask: &str, reason: &str) -> Result<Vec<Message>> {
+    for param in [channel, nick, mask] {
+        if needs_colon(param) {
+            return Err(MessageParseError::InvalidComponent {
+                value: param.to_owned(),
+            });
+        }
+    }
+
+    let ban = MessageBuilder::new()
+        .command("MODE")
+        .arg(channel)
+        .arg("+b")
+        .arg(mask)
+        .build()?;
+
+    let kick = MessageBuilder::new()
+        .command("KICK")
+        .arg(channel)
+        .arg(nick)
+        .trailing(reason)
+        .build()?;
+
+    Ok(vec![ban, kick])
+}
+
+/// Constructs as many messages containing MODE commands as needed to apply the given changes to the
+/// target, where each change is whether the mode is being added, the mode character, and its parameter.
+/// Consecutive changes share a sign, and no message contains more than `modes_per_line` parameters,
+/// such as the limit advertised by the MODES token of RPL_ISUPPORT (005). This is the inverse of `Mode::changes`.
+pub fn mode(
+    target: &str,
+    changes: &[(bool, char, Option<&str>)],
+    modes_per_line: usize,
+) -> Result<Vec<Message>> {
+    let mut lines = vec![Vec::new()];
+    let mut params = 0;
+
+    for change in changes {
+        if let Some(param) = change.2 {
+            if needs_colon(param) {
+                return Err(MessageParseError::InvalidComponent {
+                    value: param.to_owned(),
+                });
+            }
+
+            if params == modes_per_line.max(1) {
+                lines.push(Vec::new());
+                params = 0;
+            }
+
+            params += 1;
+        }
+
+        lines.last_mut().unwrap().push(change);
+    }
+
+    let mut messages = Vec::new();
+
+    for line in lines.iter().filter(|line| !line.is_empty()) {
+        let mut modes = String::new();
+        let mut adding = None;
+
+        for &&(add, mode, _) in line {
+            if adding != Some(add) {
+                modes.push(if add { '+' } else { '-' });
+                adding = Some(add);
+            }
+
+            modes.push(mode);
+        }
+
+        let mut builder = MessageBuilder::new()
+            .command("MODE")
+            .arg(target)
+            .arg(&modes);
+        for param in line.iter().filter_map(|change| change.2) {
+            builder = builder.arg(param);
+        }
+
+        messages.push(builder.build()?);
+    }
+
+    Ok(messages)
+}
+
+/// Constructs as many messages containing PRIVMSG commands as needed to send the given message
+/// to the specified targets without any of them exceeding `MAX_MESSAGE_LENGTH` bytes on the wire.
+/// The message is split on character boundaries, preferring to split after a space when possible.
+pub fn priv_msg_split(targets: &str, message: &str) -> Result<Vec<Message>> {
+    // NOTE: Account for "PRIVMSG ", the targets, " :", and the trailing CRLF.
+    let overhead = "PRIVMSG ".len() + targets.len() + " :".len() + 2;
+    let max_len = MAX_MESSAGE_LENGTH.saturating_sub(overhead);
+
+    let mut messages = Vec::new();
+    let mut remaining = message;
+
+    loop {
+        if remaining.len() <= max_len {
+            messages.push(priv_msg(targets, remaining)?);
+            return Ok(messages);
+        }
+
+        let mut split = max_len;
+        while !remaining.is_char_boundary(split) {
+            split -= 1;
+        }
+
+        if split == 0 {
+            return Err(MessageParseError::MessageTooLong {
+                len: overhead + remaining.len(),
+            });
+        }
+
+        if let Some(space) = remaining[..split].rfind(' ') {
+            split = space + 1;
+        }
+
+        let (chunk, rest) = remaining.split_at(split);
+        messages.push(priv_msg(targets, chunk)?);
+        remaining = rest;
+    }
+}
+
+/// Constructs a message containing a REGISTER command from the `draft/account-registration` extension,
+/// registering the account with the given password. If `email` is not specified, it is sent as `*`.
+pub fn register(account: &str, email: Option<&str>, password: &str) -> Result<Message> {
+    let email = email.unwrap_or("*");
+
+    for param in [account, email] {
+        if needs_colon(param) {
+            return Err(MessageParseError::InvalidComponent {
+                value: param.to_owned(),
+            });
+        }
+    }
+
+    MessageBuilder::new()
+        .command("REGISTER")
+        .arg(account)
+        .arg(email)
+        .arg(password)
+        .build()
+}
+
+/// Constructs a message containing a WALLOPS command broadcasting the given message.
+pub fn wallops(message: &str) -> Result<Message> {
+    Message::try_from(format!("WALLOPS :{}", message))
+}
+
+/// Constructs a message containing a WEBIRC command, used by web gateways to pass along the
+/// hostname and IP address of the real client.
+pub fn webirc(password: &str, gateway: &str, hostname: &str, ip: &str) -> Result<Message> {
+    Message::try_from(format!(
+        "WEBIRC {} {} {} {}",
+        password, gateway, hostname, ip
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::PrivMsg;
+    use anyhow::{Context, Result};
+
+    fn split_bodies(messages: &[Message]) -> Result<Vec<&str>> {
+        messages
+            .iter()
+            .map(|message| {
+                let PrivMsg(_, body) = message.command().context("Invalid privmsg command.")?;
+                Ok(body)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_notice() -> Result<()> {
+        let message = notice("#memes", "memes for all")?;
+
+        assert_eq!("NOTICE #memes :memes for all", message.raw_message());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ctcp_reply_round_trip() -> Result<()> {
+        let message = ctcp_reply("robot", "VERSION", Some("memebot 1.0"))?;
+
+        assert_eq!(
+            "NOTICE robot :\x01VERSION memebot 1.0\x01",
+            message.raw_message()
+        );
+        assert_eq!(Some(("VERSION", Some("memebot 1.0"))), message.ctcp_reply());
+        assert_eq!(None, message.ctcp_request());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ctcp_round_trip() -> Result<()> {
+        let message = ctcp("robot", "PING", Some("1234"))?;
+
+        assert_eq!("PRIVMSG robot :\x01PING 1234\x01", message.raw_message());
+        assert_eq!(Some(("PING", Some("1234"))), message.ctcp_request());
+        assert_eq!(None, message.ctcp_reply());
+
+        let message = ctcp("robot", "VERSION", None)?;
+
+        assert_eq!(Some(("VERSION", None)), message.ctcp_request());
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_round_trip() -> Result<()> {
+        let message = action("#memes", "dances wildly")?;
+
+        assert_eq!(
+            "PRIVMSG #memes :\x01ACTION dances wildly\x01",
+            message.raw_message()
+        );
+        assert_eq!(
+            Some(("ACTION", Some("dances wildly"))),
+            message.ctcp_request()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_rejects_delimiter() {
+        let result = action("#memes", "dances\x01 wildly");
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::InvalidComponent { .. })
+        ));
+    }
+
+    #[test]
+    fn test_ctcp_reply_rejects_delimiter() {
+        let result = ctcp_reply("robot", "PING", Some("1\x01PRIVMSG"));
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::InvalidComponent { .. })
+        ));
+    }
+
+    #[test]
+    fn test_away() -> Result<()> {
+        assert_eq!("AWAY", away(None)?.raw_message());
+        assert_eq!(
+            "AWAY :Gone fishing",
+            away(Some("Gone fishing"))?.raw_message()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_quit() -> Result<()> {
+        assert_eq!("QUIT", quit(None)?.raw_message());
+        assert_eq!("QUIT :gone", quit(Some("gone"))?.raw_message());
+        Ok(())
+    }
+
+    #[test]
+    fn test_invite() -> Result<()> {
+        assert_eq!(
+            "INVITE newbie #secret",
+            invite("newbie", "#secret")?.raw_message()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_kick() -> Result<()> {
+        assert_eq!("KICK #a nick", kick("#a", "nick", None)?.raw_message());
+        assert_eq!(
+            "KICK #a nick :spam",
+            kick("#a", "nick", Some("spam"))?.raw_message()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_kickban() -> Result<()> {
+        let messages = kickban("#memes", "spammer", "*!*@spam.com", "no spam")?;
+
+        assert_eq!(2, messages.len());
+        assert_eq!("MODE", messages[0].raw_command());
+        assert_eq!(
+            vec!["#memes", "+b", "*!*@spam.com"],
+            messages[0].raw_args().collect::<Vec<_>>()
+        );
+        assert_eq!("KICK", messages[1].raw_command());
+        assert_eq!(
+            vec!["#memes", "spammer", "no spam"],
+            messages[1].raw_args().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_kickban_rejects_injection() {
+        let result = kickban("#memes", "spammer", "*!*@spam.com :x\r\nQUIT", "no spam");
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::InvalidComponent { .. })
+        ));
+
+        let result = kickban("#memes", "spammer", "*!*@spam.com", "bye\r\nQUIT");
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::InvalidComponent { .. })
+        ));
+    }
+
+    #[test]
+    fn test_mode_groups_signs() -> Result<()> {
+        let changes = [
+            (true, 'o', Some("nick1")),
+            (true, 'm', None),
+            (false, 'v', Some("nick2")),
+            (false, 't', None),
+        ];
+        let messages = mode("#memes", &changes, 3)?;
+
+        assert_eq!(1, messages.len());
+        assert_eq!("MODE #memes +om-vt nick1 nick2", messages[0].raw_message());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_splits_by_modes_per_line() -> Result<()> {
+        let changes = [
+            (true, 'o', Some("a")),
+            (true, 'o', Some("b")),
+            (true, 'n', None),
+            (true, 'o', Some("c")),
+            (false, 'v', Some("d")),
+        ];
+        let messages = mode("#memes", &changes, 2)?;
+
+        assert_eq!(2, messages.len());
+        assert_eq!("MODE #memes +oon a b", messages[0].raw_message());
+        assert_eq!("MODE #memes +o-v c d", messages[1].raw_message());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_round_trips_through_changes() -> Result<()> {
+        use crate::command::Mode;
+        use anyhow::Context;
+
+        let changes = vec![(true, 'b', Some("*!*@spam.com")), (false, 'l', None)];
+        let messages = mode("#memes", &changes, 3)?;
+        let parsed: Mode = messages[0].command().context("Invalid mode command.")?;
+
+        assert_eq!(changes, parsed.changes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_rejects_invalid_param() {
+        let result = mode("#memes", &[(true, 'k', Some("a b"))], 3);
+
+        assert!(matches!(
+            result,
+            Err(MessageParseError::InvalidComponent { .. })
+        ));
+    }
+
+    #[test]
+    fn test_whois() -> Result<()> {
+        assert_eq!("WHOIS robot", whois(None, "robot")?.raw_message());
+        assert_eq!(
+            "WHOIS irc.memes.com robot",
+            whois(Some("irc.memes.com"), "robot")?.raw_message()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_part() -> Result<()> {
+        assert_eq!("PART #a,#b", part("#a,#b", None)?.raw_message());
+        assert_eq!("PART #a :bye", part("#a", Some("bye"))?.raw_message());
+        Ok(())
+    }
+
+    #[test]
+    fn test_topic() -> Result<()> {
+        assert_eq!("TOPIC #a", topic("#a", None)?.raw_message());
+        assert_eq!(
+            "TOPIC #a :New topic",
+            topic("#a", Some("New topic"))?.raw_message()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_register() -> Result<()> {
+        assert_eq!(
+            "REGISTER robot robot@memes.com hunter2",
+            register("robot", Some("robot@memes.com"), "hunter2")?.raw_message()
+        );
+        assert_eq!(
+            "REGISTER robot * :hunter 2",
+            register("robot", None, "hunter 2")?.raw_message()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ison() -> Result<()> {
+        assert_eq!(
+            "ISON overlord robot",
+            ison(&["overlord", "robot"])?.raw_message()
+        );
+        assert!(matches!(
+            ison(&[]),
+            Err(MessageParseError::InvalidComponent { .. })
+        ));
+        assert!(matches!(
+            ison(&["over lord"]),
+            Err(MessageParseError::InvalidComponent { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_wallops() -> Result<()> {
+        assert_eq!(
+            "WALLOPS :server going down",
+            wallops("server going down")?.raw_message()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_priv_msg_split_short_message() -> Result<()> {
+        let messages = priv_msg_split("#memes", "memes for all")?;
+
+        assert_eq!(vec!["memes for all"], split_bodies(&messages)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_priv_msg_split_prefers_spaces() -> Result<()> {
+        let text = format!("{} {}", "a".repeat(400), "b".repeat(200));
+        let messages = priv_msg_split("#memes", &text)?;
+        let bodies = split_bodies(&messages)?;
+
+        assert_eq!(
+            vec![format!("{} ", "a".repeat(400)), "b".repeat(200)],
+            bodies
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_priv_msg_split_multibyte_at_boundary() -> Result<()> {
+        // NOTE: "PRIVMSG #memes :" and CRLF leave 494 bytes, which falls in the middle of an emoji.
+        let text = format!("{}{}", "a".repeat(493), "💖".repeat(200));
+        let messages = priv_msg_split("#memes", &text)?;
+        let bodies = split_bodies(&messages)?;
+
+        assert_eq!(text, bodies.concat());
+        assert_eq!("a".repeat(493), bodies[0]);
+
+        for message in &messages {
+            assert!(message.raw_message().len() + 2 <= MAX_MESSAGE_LENGTH);
+        }
+
+        Ok(())
+    }
+}
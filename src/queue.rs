@@ -0,0 +1,197 @@
+//! The queue module contains a helper for throttling outgoing messages, so that a client stays
+//! within the flood limits enforced by servers.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::message::Message;
+
+/// A queue of outgoing messages, which releases them according to a token bucket. Up to `burst`
+/// messages can be sent at once, after which one further message is allowed each `interval`.
+/// Scheduling is left to the caller, which should poll the queue periodically and send whatever
+/// messages it returns.
+#[derive(Debug)]
+pub struct SendQueue {
+    messages: VecDeque<Message>,
+    burst: u32,
+    interval: Duration,
+    tokens: u32,
+    last_refill: Option<Instant>,
+}
+
+impl SendQueue {
+    /// Creates an empty queue whose bucket starts full. An `interval` of zero disables throttling.
+    /// A `burst` of zero is treated as one, since a bucket that holds no tokens would never release
+    /// a message.
+    pub fn new(burst: u32, interval: Duration) -> SendQueue {
+        let burst = burst.max(1);
+
+        SendQueue {
+            messages: VecDeque::new(),
+            burst,
+            interval,
+            tokens: burst,
+            last_refill: None,
+        }
+    }
+
+    /// Adds a message to the back of the queue.
+    pub fn push(&mut self, message: Message) {
+        self.messages.push_back(message);
+    }
+
+    /// Returns the next message if the rate limit allows it to be sent now.
+    pub fn poll_ready(&mut self) -> Option<Message> {
+        self.poll_ready_at(Instant::now())
+    }
+
+    /// Returns the next message if the rate limit allows it to be sent at the given instant.
+    pub fn poll_ready_at(&mut self, now: Instant) -> Option<Message> {
+        if self.messages.is_empty() {
+            return None;
+        }
+
+        if self.interval.is_zero() {
+            return self.messages.pop_front();
+        }
+
+        self.refill(now);
+
+        if self.tokens == 0 {
+            return None;
+        }
+
+        self.tokens -= 1;
+        self.messages.pop_front()
+    }
+
+    /// Returns the number of messages waiting to be sent.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if there are no messages waiting to be sent.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let last_refill = match self.last_refill {
+            Some(last_refill) if self.tokens < self.burst => last_refill,
+            // NOTE: A full bucket can't earn tokens, so idle time shouldn't count towards the
+            // next one.
+            _ => {
+                self.last_refill = Some(now);
+                return;
+            }
+        };
+
+        let elapsed = now.saturating_duration_since(last_refill);
+        let earned = elapsed.as_nanos() / self.interval.as_nanos();
+
+        if earned == 0 {
+            return;
+        }
+
+        let missing = self.burst - self.tokens;
+
+        if earned >= u128::from(missing) {
+            self.tokens = self.burst;
+            self.last_refill = Some(now);
+        } else {
+            // NOTE: `earned` is less than `missing`, so it fits within a u32.
+            let earned = earned as u32;
+
+            self.tokens += earned;
+            self.last_refill = Some(last_refill + self.interval * earned);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    fn queue_with(count: usize, burst: u32, interval: Duration) -> Result<SendQueue> {
+        let mut queue = SendQueue::new(burst, interval);
+
+        for index in 0..count {
+            queue.push(Message::try_from(format!(
+                "PRIVMSG #memes :meme {}",
+                index
+            ))?);
+        }
+
+        Ok(queue)
+    }
+
+    #[test]
+    fn test_burst_then_drain() -> Result<()> {
+        let interval = Duration::from_secs(2);
+        let mut queue = queue_with(6, 3, interval)?;
+        let start = Instant::now();
+
+        for index in 0..3 {
+            let message = queue.poll_ready_at(start).expect("burst message");
+            assert_eq!(
+                format!("PRIVMSG #memes :meme {}", index),
+                message.raw_message()
+            );
+        }
+
+        assert!(queue.poll_ready_at(start).is_none());
+        assert!(queue.poll_ready_at(start + interval / 2).is_none());
+        assert!(queue.poll_ready_at(start + interval).is_some());
+        assert!(queue.poll_ready_at(start + interval).is_none());
+        assert!(queue.poll_ready_at(start + interval * 2).is_some());
+        assert!(queue.poll_ready_at(start + interval * 3).is_some());
+        assert!(queue.is_empty());
+        assert!(queue.poll_ready_at(start + interval * 4).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_refill_caps_at_burst() -> Result<()> {
+        let interval = Duration::from_secs(1);
+        let mut queue = queue_with(5, 2, interval)?;
+        let start = Instant::now();
+
+        assert!(queue.poll_ready_at(start).is_some());
+        assert!(queue.poll_ready_at(start).is_some());
+
+        let later = start + interval * 60;
+        assert!(queue.poll_ready_at(later).is_some());
+        assert!(queue.poll_ready_at(later).is_some());
+        assert!(queue.poll_ready_at(later).is_none());
+        assert_eq!(1, queue.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_interval_is_unthrottled() -> Result<()> {
+        let mut queue = queue_with(4, 0, Duration::ZERO)?;
+        let start = Instant::now();
+
+        for _ in 0..4 {
+            assert!(queue.poll_ready_at(start).is_some());
+        }
+        assert!(queue.poll_ready_at(start).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_burst_is_treated_as_one() -> Result<()> {
+        let interval = Duration::from_secs(1);
+        let mut queue = queue_with(3, 0, interval)?;
+        let start = Instant::now();
+
+        assert!(queue.poll_ready_at(start).is_some());
+        assert!(queue.poll_ready_at(start).is_none());
+        assert!(queue.poll_ready_at(start + interval).is_some());
+        assert!(queue.poll_ready_at(start + interval).is_none());
+        assert!(queue.poll_ready_at(start + interval * 2).is_some());
+        assert!(queue.is_empty());
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 //! The tag module contains everything needed to perform strongly typed access
 //! to tags associated with a message.
 
+use std::borrow::Cow;
 use std::ops::Range;
 use std::slice::Iter;
 
@@ -21,6 +22,20 @@ impl<'a> TagIter<'a> {
     }
 }
 
+impl<'a> TagIter<'a> {
+    /// Adapts this iterator to yield unescaped tag values rather than raw values.
+    pub fn unescaped(self) -> impl Iterator<Item = (&'a str, Option<Cow<'a, str>>)> {
+        self.map(|(key, value)| (key, value.map(unescape)))
+    }
+
+    /// Finds the raw value of the first tag with the given key. The outer `Option` is whether the
+    /// tag is present and the inner `Option` is whether it has a value.
+    pub fn find_value(mut self, key: &str) -> Option<Option<&'a str>> {
+        self.find(|&(tag_key, _)| tag_key == key)
+            .map(|(_, value)| value)
+    }
+}
+
 impl<'a> Iterator for TagIter<'a> {
     type Item = (&'a str, Option<&'a str>);
 
@@ -52,7 +67,8 @@ pub trait Tag<'a> {
     const NAME: &'static str;
 
     /// This method attempts to parse the tag input into a strongly typed representation.
-    /// If parsing failes, it returns `None`.
+    /// If parsing failes, it returns `None`. The value is passed as it appears on the wire, so
+    /// implementations of tags whose values may contain escapes should apply `unescape`.
     fn parse(tag: Option<&'a str>) -> Option<Self>
     where
         Self: Sized;
@@ -67,3 +83,395 @@ pub trait Tag<'a> {
             .and_then(|(_, value)| Self::parse(value))
     }
 }
+
+/// Determines whether a tag key belongs to a client-only tag, which is prefixed with `+`,
+/// such as `+draft/reply`. Client-only tags are relayed by the server and should not be trusted.
+pub fn is_client_only(key: &str) -> bool {
+    key.starts_with('+')
+}
+
+/// Determines whether a tag key matches the given name, ignoring the `+` prefix of client-only tags
+/// on either of them, so that `+typing` and `typing` are considered the same. As this allows a
+/// client-only tag to match the name of a server tag, it should not be used to look up trusted tags.
+pub fn name_matches(key: &str, name: &str) -> bool {
+    key.strip_prefix('+').unwrap_or(key) == name.strip_prefix('+').unwrap_or(name)
+}
+
+/// Unescapes a raw tag value according to the IRCv3 escaping rules, borrowing the value when
+/// it contains no escapes. Unknown escapes drop the backslash and a trailing lone backslash is removed.
+pub fn unescape(value: &str) -> Cow<'_, str> {
+    if !value.contains('\\') {
+        return Cow::Borrowed(value);
+    }
+
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some(':') => result.push(';'),
+            Some('s') => result.push(' '),
+            Some('r') => result.push('\r'),
+            Some('n') => result.push('\n'),
+            Some(c) => result.push(c),
+            None => (),
+        }
+    }
+
+    Cow::Owned(result)
+}
+
+/// Escapes a tag value according to the IRCv3 escaping rules, so that it can't break the framing
+/// of a message. The value is borrowed when it contains nothing to escape.
+pub fn escape(value: &str) -> Cow<'_, str> {
+    if !value.contains([';', ' ', '\\', '\r', '\n']) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut result = String::with_capacity(value.len() + 8);
+    for c in value.chars() {
+        match c {
+            ';' => result.push_str("\\:"),
+            ' ' => result.push_str("\\s"),
+            '\\' => result.push_str("\\\\"),
+            '\r' => result.push_str("\\r"),
+            '\n' => result.push_str("\\n"),
+            c => result.push(c),
+        }
+    }
+
+    Cow::Owned(result)
+}
+
+/// The state of a user's typing indicator.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TypingState {
+    /// The user is actively typing.
+    Active,
+    /// The user has typed something but has paused.
+    Paused,
+    /// The user has stopped typing without sending a message.
+    Done,
+}
+
+/// Represents the `+typing` client tag, which indicates a user's typing state.
+/// Unknown typing states are not matched.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Typing(pub TypingState);
+
+impl<'a> Tag<'a> for Typing {
+    const NAME: &'static str = "+typing";
+
+    fn parse(tag: Option<&'a str>) -> Option<Typing> {
+        let state = match tag? {
+            "active" => TypingState::Active,
+            "paused" => TypingState::Paused,
+            "done" => TypingState::Done,
+            _ => return None,
+        };
+
+        Some(Typing(state))
+    }
+}
+
+/// Represents the IRCv3 `time` tag, which is the time the server processed a message as an
+/// RFC3339 timestamp in UTC, such as `2019-11-30T12:34:56.789Z`. Malformed timestamps are not matched.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ServerTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    /// The fractional part of the second, in nanoseconds.
+    pub nanosecond: u32,
+}
+
+impl<'a> Tag<'a> for ServerTime {
+    const NAME: &'static str = "time";
+
+    fn parse(tag: Option<&'a str>) -> Option<ServerTime> {
+        let value = tag?.strip_suffix(['Z', 'z'])?;
+        let (date, time) = value.split_once(['T', 't'])?;
+        let (time, fraction) = match time.split_once('.') {
+            Some((time, fraction)) => (time, Some(fraction)),
+            None => (time, None),
+        };
+
+        let mut date = date.split('-');
+        let year = parse_digits(date.next()?, 4)?;
+        let month = parse_digits(date.next()?, 2)?;
+        let day = parse_digits(date.next()?, 2)?;
+
+        let mut time = time.split(':');
+        let hour = parse_digits(time.next()?, 2)?;
+        let minute = parse_digits(time.next()?, 2)?;
+        let second = parse_digits(time.next()?, 2)?;
+
+        if date.next().is_some() || time.next().is_some() {
+            return None;
+        }
+
+        // NOTE: Only the first nine digits of the fraction fit in nanoseconds, the rest are dropped.
+        let nanosecond = match fraction {
+            Some(fraction) if !fraction.is_empty() => {
+                // NOTE: The digits are checked first, so slicing by byte can't split a character.
+                if !fraction.bytes().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+
+                let digits = &fraction[..fraction.len().min(9)];
+
+                parse_digits(digits, digits.len())? * 10u32.pow(9 - digits.len() as u32)
+            }
+            Some(_) => return None,
+            None => 0,
+        };
+
+        let valid = (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day)
+            && hour < 24
+            && minute < 60
+            && second <= 60;
+
+        valid.then_some(ServerTime {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            hour: hour as u8,
+            minute: minute as u8,
+            second: second as u8,
+            nanosecond,
+        })
+    }
+}
+
+fn parse_digits(value: &str, len: usize) -> Option<u32> {
+    if value.len() != len || !value.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    value.bytes().try_fold(0u32, |acc, c| {
+        acc.checked_mul(10)?.checked_add(u32::from(c - b'0'))
+    })
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Message;
+    use anyhow::Result;
+
+    #[test]
+    fn test_unescape_without_escapes_borrows() {
+        assert!(matches!(unescape("plain"), Cow::Borrowed("plain")));
+    }
+
+    #[test]
+    fn test_unescape_each_sequence() {
+        assert_eq!(";", unescape("\\:"));
+        assert_eq!(" ", unescape("\\s"));
+        assert_eq!("\\", unescape("\\\\"));
+        assert_eq!("\r", unescape("\\r"));
+        assert_eq!("\n", unescape("\\n"));
+        assert_eq!("d", unescape("\\d"));
+        assert_eq!("a", unescape("a\\"));
+    }
+
+    #[test]
+    fn test_unescape_several_in_sequence() {
+        assert_eq!("a; b\\c\r\nd", unescape("a\\:\\sb\\\\c\\r\\n\\d\\"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert!(matches!(escape("plain"), Cow::Borrowed("plain")));
+        assert_eq!("a\\:\\sb\\\\c\\r\\n", escape("a; b\\c\r\n"));
+    }
+
+    #[test]
+    fn test_escape_parse_unescape_is_identity() -> Result<()> {
+        let mut values = vec![
+            String::from("a;b c\\d\r\ne"),
+            String::from("\\"),
+            String::from("\\s;;"),
+            String::from("ümlaut ☃"),
+        ];
+
+        // NOTE: Every byte other than NUL forms a valid single character value.
+        values.extend((1..=255u8).map(|byte| char::from(byte).to_string()));
+
+        for value in values {
+            let msg = Message::try_from(format!("@key={} TAGMSG #memes", escape(&value)))?;
+            let (_, raw) = msg.raw_tags().next().unwrap();
+
+            assert_eq!(value, unescape(raw.unwrap()));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_value() -> Result<()> {
+        let msg = Message::try_from("@a=foo\\sbar;b;c=1;c=2 TAGMSG #memes")?;
+
+        assert_eq!(Some(Some("foo\\sbar")), msg.raw_tags().find_value("a"));
+        assert_eq!(Some(None), msg.raw_tags().find_value("b"));
+        assert_eq!(Some(Some("1")), msg.raw_tags().find_value("c"));
+        assert_eq!(None, msg.raw_tags().find_value("d"));
+        assert_eq!(
+            None,
+            Message::try_from("TAGMSG #memes")?
+                .raw_tags()
+                .find_value("a")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unescaped_tag_iter() -> Result<()> {
+        let msg = Message::try_from("@a=foo\\sbar;b;c=plain TAGMSG #memes")?;
+        let tags: Vec<_> = msg.raw_tags().unescaped().collect();
+
+        assert_eq!(
+            vec![
+                ("a", Some(Cow::from("foo bar"))),
+                ("b", None),
+                ("c", Some(Cow::from("plain")))
+            ],
+            tags
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_only_tags() -> Result<()> {
+        let msg = Message::try_from("@+typing=active;account=x TAGMSG #memes")?;
+        let tags: Vec<_> = msg
+            .raw_tags()
+            .map(|(key, _)| (key, is_client_only(key)))
+            .collect();
+
+        assert_eq!(vec![("+typing", true), ("account", false)], tags);
+        Ok(())
+    }
+
+    #[test]
+    fn test_name_matches_ignores_client_only_prefix() -> Result<()> {
+        let msg = Message::try_from("@+typing=active;account=x TAGMSG #memes")?;
+        let find = |name| {
+            msg.raw_tags()
+                .find(|&(key, _)| name_matches(key, name))
+                .and_then(|(_, value)| value)
+        };
+
+        assert_eq!(Some("active"), find("typing"));
+        assert_eq!(Some("active"), find("+typing"));
+        assert_eq!(Some("x"), find("account"));
+        assert_eq!(Some("x"), find("+account"));
+        assert_eq!(None, find("typing/extra"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_typing_tag() -> Result<()> {
+        let states = [
+            ("active", TypingState::Active),
+            ("paused", TypingState::Paused),
+            ("done", TypingState::Done),
+        ];
+
+        for (value, state) in states.iter() {
+            let msg = Message::try_from(format!("@+typing={} TAGMSG #memes", value))?;
+
+            assert_eq!(Some(Typing(*state)), msg.tag::<Typing>());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_typing_tag_with_unknown_state() -> Result<()> {
+        let msg = Message::try_from("@+typing=dancing TAGMSG #memes")?;
+
+        assert_eq!(None, msg.tag::<Typing>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_time_tag_with_fractional_seconds() -> Result<()> {
+        let msg = Message::try_from("@time=2019-11-30T12:34:56.789Z PING :memes")?;
+        let expected = ServerTime {
+            year: 2019,
+            month: 11,
+            day: 30,
+            hour: 12,
+            minute: 34,
+            second: 56,
+            nanosecond: 789_000_000,
+        };
+
+        assert_eq!(Some(expected), msg.tag::<ServerTime>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_time_tag_without_fractional_seconds() -> Result<()> {
+        let msg = Message::try_from("@time=2020-02-29T00:00:00Z PING :memes")?;
+        let time = msg.tag::<ServerTime>().unwrap();
+
+        assert_eq!((2020, 2, 29), (time.year, time.month, time.day));
+        assert_eq!(
+            (0, 0, 0, 0),
+            (time.hour, time.minute, time.second, time.nanosecond)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_time_tag_with_malformed_value() {
+        let values = [
+            "",
+            "2019-11-30",
+            "2019-11-30T12:34:56",
+            "2019-11-30T12:34:56.Z",
+            "2019-13-30T12:34:56Z",
+            "2019-02-29T12:34:56Z",
+            "2019-11-30T24:00:00Z",
+            "2019-11-30T12:34:56+01:00",
+            "19-11-30T12:34:56Z",
+            "2019-11-30T12:34:5xZ",
+            "2019-11-30T12:34:56.12345678901x2Z",
+            "2019-11-30T12:34:56.12345678üZ",
+        ];
+
+        for value in values.iter() {
+            assert_eq!(None, ServerTime::parse(Some(value)), "{}", value);
+        }
+
+        assert_eq!(None, ServerTime::parse(None));
+
+        let msg = Message::try_from("@time=2019-11-30T12:34:56.12345678üZ PING :x").unwrap();
+        assert_eq!(None, msg.tag::<ServerTime>());
+        assert_eq!(
+            Some(123_456_789),
+            ServerTime::parse(Some("2019-11-30T12:34:56.1234567891Z")).map(|time| time.nanosecond)
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! The whois module contains an accumulator for assembling the numerics a server
+//! sends in reply to WHOIS queries into a single record per nick.
+
+use std::collections::HashMap;
+
+use crate::casemapping::CaseMapping;
+use crate::command::{
+    EndOfWhois, WhoisActually, WhoisHelpOp, WhoisHost, WhoisIdle, WhoisOperator, WhoisRegNick,
+    WhoisSecure, WhoisServer, WhoisUser,
+};
+use crate::command_match;
+use crate::message::Message;
+
+/// The details of a nick gathered from the replies to a WHOIS query. Details the server didn't
+/// send are `None`, and flags the server didn't send are `false`.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct WhoisInfo {
+    /// The nick that was queried, as spelled by the server.
+    pub nick: String,
+    pub user: Option<String>,
+    pub host: Option<String>,
+    pub realname: Option<String>,
+    /// The server the nick is connected to and its description, from WHOISSERVER (312).
+    pub server: Option<String>,
+    pub server_info: Option<String>,
+    /// The number of seconds the nick has been idle, from WHOISIDLE (317).
+    pub idle: Option<u64>,
+    /// The time the nick connected as a UNIX timestamp, from WHOISIDLE (317).
+    pub signon: Option<u64>,
+    /// Whether the nick is registered, from WHOISREGNICK (307).
+    pub registered: bool,
+    /// Whether the nick is an IRC operator, from WHOISOPERATOR (313).
+    pub operator: bool,
+    /// Whether the nick is available for help, from WHOISHELPOP (310).
+    pub helpop: bool,
+    /// Whether the nick is using a secure connection, from WHOISSECURE (671).
+    pub secure: bool,
+    /// The actual host and IP of the nick, from WHOISACTUALLY (338), which may differ from the
+    /// host shown to other users.
+    pub actual_host: Option<String>,
+    pub actual_ip: Option<String>,
+    /// The message describing the host and IP the nick is connecting from, from WHOISHOST (378).
+    pub connecting_from: Option<String>,
+}
+
+/// Accumulates the replies to WHOIS queries until each query ends with an ENDOFWHOIS (318).
+/// Replies are keyed by the queried nick under the server's casemapping, so the replies to
+/// several queries can be interleaved.
+#[derive(Clone, Debug, Default)]
+pub struct WhoisAccumulator {
+    case_mapping: CaseMapping,
+    pending: HashMap<String, WhoisInfo>,
+}
+
+impl WhoisAccumulator {
+    pub fn new(case_mapping: CaseMapping) -> WhoisAccumulator {
+        WhoisAccumulator {
+            case_mapping,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Applies a reply to a WHOIS query to the details of the queried nick. Returns the details of the
+    /// nick once the message is the ENDOFWHOIS (318) that ends its query, otherwise `None`. Messages
+    /// that aren't WHOIS replies are ignored.
+    pub fn apply(&mut self, message: &Message) -> Option<WhoisInfo> {
+        command_match! {
+            message => {
+                WhoisUser(_, nick, user, host, realname) => {
+                    let info = self.entry(nick);
+
+                    // NOTE: The nick in WHOISUSER is the canonical spelling of the nick.
+                    info.nick = nick.to_owned();
+                    info.user = Some(user.to_owned());
+                    info.host = Some(host.to_owned());
+                    info.realname = Some(realname.to_owned());
+                    None
+                },
+                WhoisServer(_, nick, server, server_info) => {
+                    let info = self.entry(nick);
+
+                    info.server = Some(server.to_owned());
+                    info.server_info = Some(server_info.to_owned());
+                    None
+                },
+                WhoisIdle(_, nick, idle, signon, _) => {
+                    let info = self.entry(nick);
+
+                    info.idle = Some(idle);
+                    info.signon = signon;
+                    None
+                },
+                WhoisRegNick(_, nick, _) => {
+                    self.entry(nick).registered = true;
+                    None
+                },
+                WhoisOperator(_, nick, _) => {
+                    self.entry(nick).operator = true;
+                    None
+                },
+                WhoisHelpOp(_, nick, _) => {
+                    self.entry(nick).helpop = true;
+                    None
+                },
+                WhoisSecure(_, nick, _) => {
+                    self.entry(nick).secure = true;
+                    None
+                },
+                WhoisActually(_, nick, host, ip, _) => {
+                    let info = self.entry(nick);
+
+                    info.actual_host = host.map(str::to_owned);
+                    info.actual_ip = ip.map(str::to_owned);
+                    None
+                },
+                WhoisHost(_, nick, message) => {
+                    self.entry(nick).connecting_from = Some(message.to_owned());
+                    None
+                },
+                EndOfWhois(_, nick, _) => self.pending.remove(&self.case_mapping.to_lowercase(nick)),
+                _ => None
+            }
+        }
+    }
+
+    /// Get the details gathered so far for a nick whose query hasn't ended yet.
+    pub fn get(&self, nick: &str) -> Option<&WhoisInfo> {
+        self.pending.get(&self.case_mapping.to_lowercase(nick))
+    }
+
+    fn entry(&mut self, nick: &str) -> &mut WhoisInfo {
+        self.pending
+            .entry(self.case_mapping.to_lowercase(nick))
+            .or_insert_with(|| WhoisInfo {
+                nick: nick.to_owned(),
+                ..WhoisInfo::default()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Context, Result};
+
+    #[test]
+    fn test_accumulate_whois_replies() -> Result<()> {
+        let mut whois = WhoisAccumulator::default();
+        let replies = [
+            "311 robots Overlord o memes.com * :The Overlord",
+            "312 robots overlord irc.memes.com :Memes for all",
+            "317 robots overlord 42 1700000000 :seconds idle, signon time",
+            "307 robots overlord :is a registered nick",
+            "310 robots overlord :is available for help",
+            "671 robots overlord :is using a secure connection",
+            "338 robots overlord ~o@10.0.0.1 10.0.0.1 :actually using host",
+        ];
+
+        for reply in replies {
+            assert_eq!(None, whois.apply(&Message::try_from(reply)?));
+        }
+
+        let info = whois
+            .apply(&Message::try_from(
+                "318 robots OVERLORD :End of /WHOIS list",
+            )?)
+            .context("The query should have ended.")?;
+
+        assert_eq!("Overlord", info.nick);
+        assert_eq!(Some("o"), info.user.as_deref());
+        assert_eq!(Some("memes.com"), info.host.as_deref());
+        assert_eq!(Some("The Overlord"), info.realname.as_deref());
+        assert_eq!(Some("irc.memes.com"), info.server.as_deref());
+        assert_eq!(Some("Memes for all"), info.server_info.as_deref());
+        assert_eq!(Some(42), info.idle);
+        assert_eq!(Some(1700000000), info.signon);
+        assert!(info.registered);
+        assert!(info.helpop);
+        assert!(info.secure);
+        assert_eq!(Some("~o@10.0.0.1"), info.actual_host.as_deref());
+        assert_eq!(Some("10.0.0.1"), info.actual_ip.as_deref());
+        assert!(!info.operator);
+        assert!(whois.get("overlord").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interleaved_whois_replies() -> Result<()> {
+        let mut whois = WhoisAccumulator::new(CaseMapping::Rfc1459);
+
+        whois.apply(&Message::try_from(
+            "313 robots overlord[m] :is an IRC operator",
+        )?);
+        whois.apply(&Message::try_from(
+            "307 robots minion :is a registered nick",
+        )?);
+
+        let info = whois
+            .apply(&Message::try_from(
+                "318 robots overlord{m} :End of /WHOIS list",
+            )?)
+            .context("The query should have ended.")?;
+
+        assert_eq!("overlord[m]", info.nick);
+        assert!(info.operator);
+        assert!(!info.registered);
+        assert!(whois.get("MINION").is_some_and(|info| info.registered));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignores_other_messages() -> Result<()> {
+        let mut whois = WhoisAccumulator::default();
+
+        assert_eq!(
+            None,
+            whois.apply(&Message::try_from("PING :irc.memes.com")?)
+        );
+        assert_eq!(
+            None,
+            whois.apply(&Message::try_from("318 robots nobody :End of /WHOIS list")?)
+        );
+
+        Ok(())
+    }
+}